@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+
+use crate::{
+    data::{board_shift::BoardShift, spawn_rules::SpawnRules},
+    engine::{bitboard::Bitboard, grid::Grid},
+};
+
+use super::{heuristic::Heuristic, search_board::SearchBoard};
+
+// what a board without any moves is worth, far below anything the heuristic gives
+const LOST: f32 = -1.0e6;
+
+/// Looks `depth` moves ahead, alternating between the player picking the best move and
+/// the game spawning a tile, where every empty cell and spawn value is weighed by how
+/// likely it is under `spawn_rules`. Only one spawn per move is searched, even when the
+/// rules spawn more.
+#[derive(Debug, Clone)]
+pub struct Expectimax {
+    pub depth: u32,
+    pub spawn_rules: SpawnRules,
+    pub heuristic: Heuristic,
+    // spawn sequences less likely than this are cut off and scored by the heuristic
+    pub min_probability: f32,
+}
+
+impl Default for Expectimax {
+    fn default() -> Self {
+        Expectimax::new(3, SpawnRules::default())
+    }
+}
+
+impl Expectimax {
+    pub fn new(depth: u32, spawn_rules: SpawnRules) -> Self {
+        Expectimax {
+            depth,
+            spawn_rules,
+            heuristic: Heuristic::default(),
+            min_probability: 1.0e-4,
+        }
+    }
+
+    /// The most promising move, or None when no move changes the board.
+    pub fn best_move(&self, grid: &Grid) -> Option<BoardShift> {
+        self.evaluate_moves(grid)
+            .into_iter()
+            .max_by(|a, b| a.value.total_cmp(&b.value))
+            .map(|estimate| estimate.board_shift)
+    }
+
+    /// What the search thinks of every move that changes the board.
+    pub fn evaluate_moves(&self, grid: &Grid) -> Vec<MoveEstimate> {
+        // 4x4 games with the usual tiles are searched on a bitboard, which is much faster
+        let fits_bitboard = self
+            .spawn_rules
+            .values
+            .iter()
+            .all(|(value, _)| Bitboard::holds(*value));
+        match Bitboard::from_grid(grid).filter(|_| fits_bitboard) {
+            Some(board) => Search::new(self).evaluate_moves(&board),
+            None => Search::new(self).evaluate_moves(grid),
+        }
+    }
+}
+
+/// How one move scored in the search.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoveEstimate {
+    pub board_shift: BoardShift,
+    // heuristic value, only meaningful compared to the other moves
+    pub value: f32,
+    // points this move and the best moves after it are expected to score within the depth
+    pub points: f32,
+}
+
+// what a node of the search tree is worth
+#[derive(Debug, Clone, Copy)]
+struct Estimate {
+    value: f32,
+    points: f32,
+    // somewhere below, an unlikely spawn was scored by the heuristic instead of searched,
+    // so the estimate depends on how likely the path to this node was
+    cut_off: bool,
+}
+
+// One call to evaluate_moves, chance nodes reached again through other move orders are
+// cached. Only estimates without a cut off are, those are the same from every path.
+struct Search<'a, B> {
+    expectimax: &'a Expectimax,
+    total_weight: f32,
+    cache: HashMap<(B, u32), Estimate>,
+}
+
+impl<'a, B: SearchBoard> Search<'a, B> {
+    fn new(expectimax: &'a Expectimax) -> Self {
+        Search {
+            expectimax,
+            total_weight: expectimax
+                .spawn_rules
+                .values
+                .iter()
+                .map(|(_, weight)| *weight as f32)
+                .sum(),
+            cache: HashMap::new(),
+        }
+    }
+
+    fn evaluate_moves(&mut self, board: &B) -> Vec<MoveEstimate> {
+        let depth = self.expectimax.depth.max(1);
+        BoardShift::ALL
+            .iter()
+            .filter_map(|&shift| {
+                let (moved, points) = board.shifted(shift)?;
+                let estimate = self.spawn_node(&moved, depth - 1, 1.0);
+                Some(MoveEstimate {
+                    board_shift: shift,
+                    value: estimate.value,
+                    points: points as f32 + estimate.points,
+                })
+            })
+            .collect()
+    }
+
+    // the player's turn, `depth` moves are left to search
+    fn move_node(&mut self, board: &B, depth: u32, probability: f32) -> Estimate {
+        if depth == 0 || probability < self.expectimax.min_probability {
+            return Estimate {
+                value: self.expectimax.heuristic.evaluate(board),
+                points: 0.0,
+                // the end of the search is the same from every path
+                cut_off: depth > 0,
+            };
+        }
+        BoardShift::ALL
+            .iter()
+            .filter_map(|&shift| board.shifted(shift))
+            .map(|(moved, points)| {
+                let estimate = self.spawn_node(&moved, depth - 1, probability);
+                Estimate {
+                    points: points as f32 + estimate.points,
+                    ..estimate
+                }
+            })
+            .reduce(|best, estimate| {
+                // the moves that lost out still decided which one won
+                let cut_off = best.cut_off || estimate.cut_off;
+                // the last of several equal moves wins, like max_by
+                let best = if estimate.value >= best.value {
+                    estimate
+                } else {
+                    best
+                };
+                Estimate { cut_off, ..best }
+            })
+            .unwrap_or(Estimate {
+                value: LOST,
+                points: 0.0,
+                cut_off: false,
+            })
+    }
+
+    // the game's turn, the average over every tile that could spawn
+    fn spawn_node(&mut self, board: &B, depth: u32, probability: f32) -> Estimate {
+        if let Some(estimate) = self.cache.get(&(board.clone(), depth)) {
+            return *estimate;
+        }
+
+        let empty = board.empty_positions();
+        // a move always frees a cell, this only guards against odd spawn rules
+        if empty.is_empty() {
+            return self.move_node(board, depth, probability);
+        }
+
+        let mut expected = Estimate {
+            value: 0.0,
+            points: 0.0,
+            cut_off: false,
+        };
+        let cell_probability = 1.0 / empty.len() as f32;
+        for pos in empty.iter() {
+            for &(value, weight) in self.expectimax.spawn_rules.values.iter() {
+                let spawn_probability = cell_probability * weight as f32 / self.total_weight;
+                let spawned = board.with_tile(*pos, value);
+                let estimate = self.move_node(&spawned, depth, probability * spawn_probability);
+                expected.value += spawn_probability * estimate.value;
+                expected.points += spawn_probability * estimate.points;
+                expected.cut_off |= estimate.cut_off;
+            }
+        }
+
+        if !expected.cut_off {
+            self.cache.insert((board.clone(), depth), expected);
+        }
+        expected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{data::position::Position, engine::session::Session};
+
+    #[test]
+    fn finds_no_move_on_a_lost_board() {
+        // a checkerboard of 2s and 4s has no neighbors that could merge
+        let tiles = (0..4).flat_map(|y| {
+            (0..4).map(move |x| (Position { x, y }, if (x + y) % 2 == 0 { 2 } else { 4 }))
+        });
+        let grid = Grid::from_tiles(4, 4, tiles);
+        assert!(!grid.has_moves());
+        assert_eq!(Expectimax::default().best_move(&grid), None);
+    }
+
+    #[test]
+    fn takes_the_only_move() {
+        // everything is packed against the bottom left, only up and right move anything
+        // and the single row fits against the bottom edge, so up is the one move left
+        let tiles = [
+            (Position { x: 0, y: 0 }, 2),
+            (Position { x: 1, y: 0 }, 4),
+            (Position { x: 2, y: 0 }, 8),
+            (Position { x: 3, y: 0 }, 16),
+        ];
+        let grid = Grid::from_tiles(4, 4, tiles);
+        assert_eq!(Expectimax::default().best_move(&grid), Some(BoardShift::Up));
+    }
+
+    // the bitboard is only a faster way to run the same search
+    #[test]
+    fn searches_bitboards_and_grids_alike() {
+        let expectimax = Expectimax::new(2, SpawnRules::default());
+        let mut session = Session::new(4, 4, SpawnRules::default(), 7);
+        for _ in 0..50 {
+            let grid = session.grid.clone();
+            let board = Bitboard::from_grid(&grid).unwrap();
+            let estimates = Search::new(&expectimax).evaluate_moves(&board);
+            assert_eq!(Search::new(&expectimax).evaluate_moves(&grid), estimates);
+            match expectimax.best_move(&grid) {
+                Some(shift) => session.play(shift),
+                None => break,
+            };
+        }
+    }
+
+    // a cached chance node is reused from any path, however likely that path was
+    #[test]
+    fn caches_only_estimates_that_hold_on_every_path() {
+        let expectimax = Expectimax {
+            min_probability: 0.001,
+            ..Expectimax::new(3, SpawnRules::default())
+        };
+        let mut session = Session::new(4, 4, SpawnRules::default(), 5);
+        for shift in [BoardShift::Left, BoardShift::Down, BoardShift::Left] {
+            session.play(shift);
+        }
+        let board = Bitboard::from_grid(&session.grid).unwrap();
+        let mut search = Search::new(&expectimax);
+        search.evaluate_moves(&board);
+        assert!(!search.cache.is_empty());
+        for ((node, depth), estimate) in search.cache.iter() {
+            let fresh = Search::new(&expectimax).spawn_node(node, *depth, 1.0);
+            assert_eq!(fresh.value, estimate.value, "{node:?} at depth {depth}");
+        }
+    }
+
+    #[test]
+    fn plays_a_decent_game() {
+        let expectimax = Expectimax::new(2, SpawnRules::default());
+        let mut session = Session::new(4, 4, SpawnRules::default(), 11);
+        while let Some(shift) = expectimax.best_move(&session.grid) {
+            session.play(shift);
+        }
+        // a random player rarely gets past 256
+        assert!(
+            session.grid.highest_tile() >= Some(1024),
+            "{:?}",
+            session.grid
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use crate::data::position::Position;
+
+use super::search_board::SearchBoard;
+
+/// Scores how promising a board looks, higher is better. Every term works on the
+/// exponents of the tiles (1 for a 2, 11 for a 2048) so large tiles don't drown out the rest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Heuristic {
+    // per empty cell, room to move is what keeps a game alive
+    pub empty_weight: f32,
+    // rows and columns that only rise or only fall towards one side
+    pub monotonicity_weight: f32,
+    // neighbors close in value, which are easy to merge later
+    pub smoothness_weight: f32,
+    // the highest tile sitting in a corner, per step of its exponent
+    pub corner_weight: f32,
+}
+
+impl Default for Heuristic {
+    fn default() -> Self {
+        Heuristic {
+            empty_weight: 2.7,
+            monotonicity_weight: 1.0,
+            smoothness_weight: 0.1,
+            corner_weight: 1.0,
+        }
+    }
+}
+
+impl Heuristic {
+    pub fn evaluate(&self, board: &impl SearchBoard) -> f32 {
+        let (width, height) = (board.width(), board.height());
+        let exponent = |x: u8, y: u8| {
+            board
+                .get(Position { x, y })
+                .map_or(0.0, |value| value.trailing_zeros() as f32)
+        };
+        let rows = (0..height).map(|y| (0..width).map(|x| exponent(x, y)).collect::<Vec<_>>());
+        let columns = (0..width).map(|x| (0..height).map(|y| exponent(x, y)).collect::<Vec<_>>());
+        let lines: Vec<Vec<f32>> = rows.chain(columns).collect();
+
+        let empty = board.empty_positions().len() as f32;
+
+        // how far each line is from being sorted one way or the other
+        let monotonicity: f32 = lines
+            .iter()
+            .map(|line| {
+                let (mut rising, mut falling) = (0.0, 0.0);
+                for pair in line.windows(2) {
+                    if pair[0] < pair[1] {
+                        rising += pair[1] - pair[0];
+                    } else {
+                        falling += pair[0] - pair[1];
+                    }
+                }
+                -f32::min(rising, falling)
+            })
+            .sum();
+
+        // empty cells are skipped, they don't get in the way of a merge
+        let smoothness: f32 = lines
+            .iter()
+            .map(|line| {
+                let tiles: Vec<f32> = line.iter().copied().filter(|&e| e > 0.0).collect();
+                -tiles
+                    .windows(2)
+                    .map(|pair| (pair[0] - pair[1]).abs())
+                    .sum::<f32>()
+            })
+            .sum();
+
+        let highest = lines.iter().flatten().copied().fold(0.0, f32::max);
+        let corners = [
+            (0, 0),
+            (width - 1, 0),
+            (0, height - 1),
+            (width - 1, height - 1),
+        ];
+        let corner = if corners.iter().any(|&(x, y)| exponent(x, y) == highest) {
+            highest
+        } else {
+            0.0
+        };
+
+        self.empty_weight * empty
+            + self.monotonicity_weight * monotonicity
+            + self.smoothness_weight * smoothness
+            + self.corner_weight * corner
+    }
+}
@@ -0,0 +1,7 @@
+pub mod expectimax;
+pub mod heuristic;
+pub mod monte_carlo;
+pub mod ntuple;
+pub mod search_board;
+pub mod strategy;
+pub mod td_learning;
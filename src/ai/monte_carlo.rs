@@ -0,0 +1,257 @@
+use std::time::{Duration, Instant};
+
+use rand::prelude::*;
+use rand_pcg::Pcg64;
+
+use crate::{
+    data::{board_shift::BoardShift, position::Position, spawn_rules::SpawnRules},
+    engine::{bitboard::Bitboard, grid::Grid, session::seeded_rng},
+};
+
+use super::search_board::SearchBoard;
+
+/// Tries every move by playing random games from it to the end, and takes the move whose
+/// games scored the most points on average. The rollouts for a board are drawn from an rng
+/// seeded by `seed` and the board itself, so the same seed always picks the same moves.
+#[derive(Debug, Clone)]
+pub struct MonteCarlo {
+    // random games per move
+    pub rollouts: u32,
+    // stop rolling out once a decision has taken this long, every move still gets at least
+    // one game, and the choice is only reproducible when the limit isn't reached
+    pub time_limit: Option<Duration>,
+    pub spawn_rules: SpawnRules,
+    pub seed: u64,
+}
+
+impl Default for MonteCarlo {
+    fn default() -> Self {
+        MonteCarlo::new(100, 0)
+    }
+}
+
+impl MonteCarlo {
+    pub fn new(rollouts: u32, seed: u64) -> Self {
+        MonteCarlo {
+            rollouts,
+            time_limit: None,
+            spawn_rules: SpawnRules::default(),
+            seed,
+        }
+    }
+
+    /// The move with the best average rollout, or None when no move changes the board.
+    pub fn best_move(&self, grid: &Grid) -> Option<BoardShift> {
+        self.evaluate_moves(grid)
+            .into_iter()
+            // the first of several equal moves wins, so ties always break the same way
+            .rev()
+            .max_by(|a, b| a.average_points.total_cmp(&b.average_points))
+            .map(|estimate| estimate.board_shift)
+    }
+
+    /// How the rollouts went for every move that changes the board.
+    pub fn evaluate_moves(&self, grid: &Grid) -> Vec<RolloutEstimate> {
+        // like Expectimax, 4x4 games with the usual tiles are played out on a bitboard
+        let fits_bitboard = self
+            .spawn_rules
+            .values
+            .iter()
+            .all(|(value, _)| Bitboard::holds(*value));
+        match Bitboard::from_grid(grid).filter(|_| fits_bitboard) {
+            Some(board) => self.evaluate(&board),
+            None => self.evaluate(grid),
+        }
+    }
+
+    fn evaluate<B: SearchBoard>(&self, board: &B) -> Vec<RolloutEstimate> {
+        let started = Instant::now();
+        let mut candidates: Vec<Candidate<B>> = BoardShift::ALL
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, shift)| {
+                let (moved, points) = board.shifted(shift)?;
+                Some(Candidate {
+                    board_shift: shift,
+                    moved,
+                    points,
+                    rng: self.rollout_rng(board, index),
+                    total_points: 0,
+                    rollouts: 0,
+                })
+            })
+            .collect();
+
+        // a round plays one game from every move, so a time limit shortchanges none of them
+        for round in 0..self.rollouts.max(1) {
+            let out_of_time = self
+                .time_limit
+                .is_some_and(|limit| started.elapsed() >= limit);
+            if round > 0 && out_of_time {
+                break;
+            }
+            for candidate in candidates.iter_mut() {
+                let points = self.rollout(&candidate.moved, &mut candidate.rng);
+                candidate.total_points += u64::from(candidate.points + points);
+                candidate.rollouts += 1;
+            }
+        }
+
+        candidates
+            .into_iter()
+            .map(|candidate| RolloutEstimate {
+                board_shift: candidate.board_shift,
+                average_points: candidate.total_points as f32 / candidate.rollouts as f32,
+                rollouts: candidate.rollouts,
+            })
+            .collect()
+    }
+
+    // the points a game scores from `board`, right after a move, by moving at random until
+    // no move is left
+    fn rollout<B: SearchBoard>(&self, board: &B, rng: &mut Pcg64) -> u32 {
+        let mut board = board.clone();
+        let mut points = 0;
+        loop {
+            for _ in 0..self.spawn_rules.tiles_per_move {
+                if let Some(pos) = board.empty_positions().choose(rng) {
+                    board = board.with_tile(*pos, self.spawn_rules.choose_value(rng));
+                }
+            }
+            let mut shifts = BoardShift::ALL;
+            shifts.shuffle(rng);
+            let Some((moved, scored)) = shifts.iter().find_map(|shift| board.shifted(*shift))
+            else {
+                return points;
+            };
+            board = moved;
+            points += scored;
+        }
+    }
+
+    // the rollouts of one move only depend on the seed, the board and which move it is
+    fn rollout_rng<B: SearchBoard>(&self, board: &B, shift_index: usize) -> Pcg64 {
+        // FNV-1a with one word each for the seed, the move and every tile
+        let hash = |key: u64, word: u64| (key ^ word).wrapping_mul(0x0000_0100_0000_01b3);
+        let mut key = hash(hash(0xcbf2_9ce4_8422_2325, self.seed), shift_index as u64);
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                key = hash(key, u64::from(board.get(Position { x, y }).unwrap_or(0)));
+            }
+        }
+        seeded_rng(key)
+    }
+}
+
+// one move being rolled out
+struct Candidate<B> {
+    board_shift: BoardShift,
+    moved: B,
+    points: u32,
+    rng: Pcg64,
+    total_points: u64,
+    rollouts: u32,
+}
+
+/// How the random games after one move went.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RolloutEstimate {
+    pub board_shift: BoardShift,
+    // points scored by the move and the random game after it, averaged over the rollouts
+    pub average_points: f32,
+    pub rollouts: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::session::Session;
+
+    #[test]
+    fn repeats_itself_given_a_seed() {
+        let session = Session::new(4, 4, SpawnRules::default(), 5);
+        let estimates = MonteCarlo::new(20, 1).evaluate_moves(&session.grid);
+        assert_eq!(
+            MonteCarlo::new(20, 1).evaluate_moves(&session.grid),
+            estimates
+        );
+        assert_ne!(
+            MonteCarlo::new(20, 2).evaluate_moves(&session.grid),
+            estimates
+        );
+        assert!(estimates.iter().all(|estimate| estimate.rollouts == 20));
+    }
+
+    // the seed and the move are hashed one after the other, so swapping them changes the games
+    #[test]
+    fn rolls_out_other_games_for_a_swapped_seed_and_move() {
+        let grid = Session::new(4, 4, SpawnRules::default(), 3).grid;
+        let rng = |seed, shift_index| MonteCarlo::new(1, seed).rollout_rng(&grid, shift_index);
+        assert_ne!(rng(0, 1), rng(1, 0));
+        assert_ne!(rng(2, 3), rng(3, 2));
+    }
+
+    #[test]
+    fn takes_the_only_move() {
+        // the same board as in Expectimax, up is the one move that changes anything
+        let tiles = [
+            (Position { x: 0, y: 0 }, 2),
+            (Position { x: 1, y: 0 }, 4),
+            (Position { x: 2, y: 0 }, 8),
+            (Position { x: 3, y: 0 }, 16),
+        ];
+        let grid = Grid::from_tiles(4, 4, tiles);
+        assert_eq!(MonteCarlo::new(5, 0).best_move(&grid), Some(BoardShift::Up));
+
+        let lost = (0..4).flat_map(|y| {
+            (0..4).map(move |x| (Position { x, y }, if (x + y) % 2 == 0 { 2 } else { 4 }))
+        });
+        assert_eq!(
+            MonteCarlo::new(5, 0).best_move(&Grid::from_tiles(4, 4, lost)),
+            None
+        );
+    }
+
+    #[test]
+    fn stops_at_the_time_limit() {
+        let session = Session::new(4, 4, SpawnRules::default(), 9);
+        let monte_carlo = MonteCarlo {
+            time_limit: Some(Duration::ZERO),
+            ..MonteCarlo::new(1000, 0)
+        };
+        let estimates = monte_carlo.evaluate_moves(&session.grid);
+        assert!(!estimates.is_empty());
+        assert!(estimates.iter().all(|estimate| estimate.rollouts == 1));
+    }
+
+    // the bitboard is only a faster way to play the same rollouts
+    #[test]
+    fn rolls_out_bitboards_and_grids_alike() {
+        let monte_carlo = MonteCarlo::new(5, 3);
+        let mut session = Session::new(4, 4, SpawnRules::default(), 13);
+        for _ in 0..20 {
+            let grid = session.grid.clone();
+            let board = Bitboard::from_grid(&grid).unwrap();
+            assert_eq!(monte_carlo.evaluate(&grid), monte_carlo.evaluate(&board));
+            match monte_carlo.best_move(&grid) {
+                Some(shift) => session.play(shift),
+                None => break,
+            };
+        }
+    }
+
+    #[test]
+    fn plays_better_than_at_random() {
+        let monte_carlo = MonteCarlo::new(10, 0);
+        let mut session = Session::new(4, 4, SpawnRules::default(), 17);
+        while let Some(shift) = monte_carlo.best_move(&session.grid) {
+            session.play(shift);
+        }
+        // a random player rarely gets past 256
+        assert!(
+            session.grid.highest_tile() >= Some(512),
+            "{:?}",
+            session.grid
+        );
+    }
+}
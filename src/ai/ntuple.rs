@@ -0,0 +1,353 @@
+//! N-tuple networks, the value functions the `train` binary learns and the `ntuple` strategy
+//! plays with.
+//!
+//! Weights are saved in a little endian binary file:
+//!
+//! - the magic bytes `BXNT` and the format version as a `u32`, currently 1
+//! - the number of tuples as a `u32`
+//! - per tuple, its length as a `u8` followed by that many cells as `u8`s, where cell
+//!   `4 * y + x` is `(x, y)` with `y = 0` the bottom row, the same order as `Bitboard`
+//! - per tuple, in the same order, `16^length` weights as `f32`s. The weight for a set of
+//!   tiles is at the index that reads the exponents of the tuple's cells as hex digits,
+//!   first cell first.
+//!
+//! The version is bumped whenever the layout or the meaning of the weights changes, and
+//! files with another version are refused.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    data::board_shift::BoardShift,
+    engine::{
+        bitboard::{Bitboard, SIZE},
+        grid::Grid,
+    },
+    storage,
+};
+
+use super::strategy::{Greedy, Strategy};
+
+const MAGIC: [u8; 4] = *b"BXNT";
+pub const FORMAT_VERSION: u32 = 1;
+// each cell is a hex digit of the weight index, 16^6 weights already take 64MB
+const MAX_TUPLE_LENGTH: usize = 6;
+const CELLS: u8 = SIZE * SIZE;
+// a corrupt file shouldn't make read_from allocate more than twice the 6-tuple layout
+const MAX_TUPLES: usize = 64;
+const MAX_WEIGHTS: usize = 8 << (4 * MAX_TUPLE_LENGTH);
+
+/// Four 6-tuples covering rectangles and lines along the edge, the layout the strongest
+/// 2048 networks use. Takes 256MB of weights.
+pub const SIX_TUPLES: &[&[u8]] = &[
+    &[0, 1, 2, 3, 4, 5],
+    &[4, 5, 6, 7, 8, 9],
+    &[0, 1, 2, 4, 5, 6],
+    &[4, 5, 6, 8, 9, 10],
+];
+
+/// Every straight line and 2x2 square up to symmetry. Learns quicker and takes 1.3MB,
+/// but tops out well below the 6-tuples.
+pub const FOUR_TUPLES: &[&[u8]] = &[
+    &[0, 1, 2, 3],
+    &[4, 5, 6, 7],
+    &[0, 1, 4, 5],
+    &[1, 2, 5, 6],
+    &[5, 6, 9, 10],
+];
+
+#[derive(Debug, Clone)]
+struct Tuple {
+    cells: Vec<u8>,
+    // the cells under each rotation and reflection of the board, they all share the weights
+    symmetries: Vec<Vec<u8>>,
+    weights: Vec<f32>,
+}
+
+impl Tuple {
+    fn new(cells: &[u8]) -> Self {
+        Tuple {
+            cells: cells.to_vec(),
+            symmetries: symmetries(cells),
+            weights: vec![0.0; 1 << (4 * cells.len())],
+        }
+    }
+
+    fn index(board: Bitboard, cells: &[u8]) -> usize {
+        cells.iter().fold(0, |index, cell| {
+            (index << 4) | ((board.0 >> (4 * cell)) & 0xf) as usize
+        })
+    }
+}
+
+// the 8 ways the board can be turned and flipped, applied to a tuple's cells
+fn symmetries(cells: &[u8]) -> Vec<Vec<u8>> {
+    let last = SIZE - 1;
+    // a quarter turn takes (x, y) to (last - y, x)
+    let rotate = |cell: &u8| SIZE * (cell % SIZE) + last - cell / SIZE;
+    // mirroring takes (x, y) to (last - x, y)
+    let mirror = |cell: &u8| cell - cell % SIZE + last - cell % SIZE;
+
+    let mut cells = cells.to_vec();
+    let mut symmetries = Vec::with_capacity(8);
+    for _ in 0..4 {
+        symmetries.push(cells.iter().map(mirror).collect());
+        cells = cells.iter().map(rotate).collect();
+        symmetries.push(cells.clone());
+    }
+    symmetries
+}
+
+/// A value function for 4x4 boards: every tuple looks at a handful of cells and reads a
+/// weight for the tiles it finds there, in all 8 orientations of the board, and the value
+/// is the sum of those weights. Trained by `td_learning::TdLearning`, the value of an
+/// afterstate (a board right after a move, before the new tile) estimates the points
+/// still to be scored from it.
+#[derive(Debug, Clone)]
+pub struct NTupleNetwork {
+    tuples: Vec<Tuple>,
+}
+
+impl NTupleNetwork {
+    /// A network with every weight at zero, panics on a cell off the board or a tuple
+    /// longer than 6 cells.
+    pub fn new(tuples: &[&[u8]]) -> Self {
+        for cells in tuples {
+            assert!(cells.len() <= MAX_TUPLE_LENGTH, "tuple too long: {cells:?}");
+            assert!(
+                cells.iter().all(|cell| *cell < CELLS),
+                "cell off the board: {cells:?}"
+            );
+        }
+        NTupleNetwork {
+            tuples: tuples.iter().map(|cells| Tuple::new(cells)).collect(),
+        }
+    }
+
+    pub fn value(&self, board: Bitboard) -> f32 {
+        self.tuples
+            .iter()
+            .map(|tuple| {
+                tuple
+                    .symmetries
+                    .iter()
+                    .map(|cells| tuple.weights[Tuple::index(board, cells)])
+                    .sum::<f32>()
+            })
+            .sum()
+    }
+
+    /// Moves the share of every tuple in the value of `board` by `delta`, split evenly
+    /// over the weights it reads.
+    pub fn adjust(&mut self, board: Bitboard, delta: f32) {
+        for tuple in self.tuples.iter_mut() {
+            let share = delta / tuple.symmetries.len() as f32;
+            for cells in tuple.symmetries.iter() {
+                tuple.weights[Tuple::index(board, cells)] += share;
+            }
+        }
+    }
+
+    /// The move worth the most points plus the value of where it leaves the board, together
+    /// with that afterstate and the points. None when no move changes the board.
+    pub fn best_move(&self, board: Bitboard) -> Option<(BoardShift, Bitboard, u32)> {
+        BoardShift::ALL
+            .into_iter()
+            .filter_map(|shift| {
+                let (after, points) = board.apply(shift);
+                (after != board).then_some((shift, after, points))
+            })
+            .map(|(shift, after, points)| {
+                let worth = points as f32 + self.value(after);
+                (worth, (shift, after, points))
+            })
+            // the first of several equal moves wins, so ties always break the same way
+            .rev()
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, best)| best)
+    }
+
+    /// Where `train` saves weights unless told otherwise, and where the `ntuple` strategy
+    /// looks for them.
+    pub fn default_path() -> Option<PathBuf> {
+        storage::data_dir().map(|dir| dir.join("ntuple.weights"))
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        NTupleNetwork::read_from(BufReader::new(File::open(path)?))
+    }
+
+    // through a temporary file like storage::save_json, the weights take a while to write
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        let mut out = BufWriter::new(File::create(&temp_path)?);
+        self.write_to(&mut out)?;
+        out.into_inner()?.sync_all()?;
+        fs::rename(temp_path, path)
+    }
+
+    pub fn write_to(&self, mut out: impl Write) -> io::Result<()> {
+        out.write_all(&MAGIC)?;
+        out.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        out.write_all(&(self.tuples.len() as u32).to_le_bytes())?;
+        for tuple in self.tuples.iter() {
+            out.write_all(&[tuple.cells.len() as u8])?;
+            out.write_all(&tuple.cells)?;
+        }
+        for tuple in self.tuples.iter() {
+            for weight in tuple.weights.iter() {
+                out.write_all(&weight.to_le_bytes())?;
+            }
+        }
+        out.flush()
+    }
+
+    pub fn read_from(mut input: impl Read) -> io::Result<Self> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+        let mut word = [0; 4];
+
+        input.read_exact(&mut word)?;
+        if word != MAGIC {
+            return Err(invalid("not an n-tuple weights file".to_string()));
+        }
+        input.read_exact(&mut word)?;
+        let version = u32::from_le_bytes(word);
+        if version != FORMAT_VERSION {
+            return Err(invalid(format!(
+                "weights file version {version}, expected {FORMAT_VERSION}"
+            )));
+        }
+
+        input.read_exact(&mut word)?;
+        let tuple_count = u32::from_le_bytes(word) as usize;
+        if tuple_count > MAX_TUPLES {
+            return Err(invalid(format!(
+                "{tuple_count} tuples, at most {MAX_TUPLES} are allowed"
+            )));
+        }
+        let mut layout = Vec::with_capacity(tuple_count);
+        let mut weight_count = 0;
+        for _ in 0..tuple_count {
+            let mut length = [0];
+            input.read_exact(&mut length)?;
+            let mut cells = vec![0; usize::from(length[0])];
+            input.read_exact(&mut cells)?;
+            if cells.len() > MAX_TUPLE_LENGTH || cells.iter().any(|cell| *cell >= CELLS) {
+                return Err(invalid(format!("invalid tuple: {cells:?}")));
+            }
+            weight_count += 1 << (4 * cells.len());
+            layout.push(cells);
+        }
+        // checked before Tuple::new allocates any of them
+        if weight_count > MAX_WEIGHTS {
+            return Err(invalid(format!(
+                "{weight_count} weights, at most {MAX_WEIGHTS} are allowed"
+            )));
+        }
+        let mut tuples: Vec<_> = layout.iter().map(|cells| Tuple::new(cells)).collect();
+        for tuple in tuples.iter_mut() {
+            let mut bytes = vec![0; 4 * tuple.weights.len()];
+            input.read_exact(&mut bytes)?;
+            for (weight, bytes) in tuple.weights.iter_mut().zip(bytes.chunks_exact(4)) {
+                *weight = f32::from_le_bytes(bytes.try_into().unwrap());
+            }
+        }
+        Ok(NTupleNetwork { tuples })
+    }
+}
+
+impl Strategy for NTupleNetwork {
+    fn choose_move(&self, grid: &Grid) -> Option<BoardShift> {
+        match Bitboard::from_grid(grid) {
+            Some(board) => self.best_move(board).map(|(shift, _, _)| shift),
+            // the weights only know 4x4 boards
+            None => Greedy.choose_move(grid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::position::Position;
+
+    fn board(tiles: &[(u8, u8, u32)]) -> Bitboard {
+        let tiles = tiles
+            .iter()
+            .map(|&(x, y, value)| (Position { x, y }, value));
+        Bitboard::from_grid(&Grid::from_tiles(SIZE, SIZE, tiles)).unwrap()
+    }
+
+    #[test]
+    fn covers_every_orientation() {
+        let symmetries = symmetries(&[0, 1, 2, 3]);
+        // the bottom row turned and flipped lands on every edge, both ways round
+        let mut distinct = symmetries.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), 8);
+        assert!(symmetries.contains(&vec![3, 2, 1, 0]));
+        assert!(symmetries.contains(&vec![15, 11, 7, 3]));
+    }
+
+    #[test]
+    fn values_turned_and_flipped_boards_alike() {
+        let mut network = NTupleNetwork::new(FOUR_TUPLES);
+        let original = board(&[(0, 0, 2), (1, 0, 4), (3, 2, 8)]);
+        network.adjust(original, 10.0);
+        // the same tiles a quarter turn round and mirrored
+        let turned = board(&[(3, 0, 2), (3, 1, 4), (1, 3, 8)]);
+        let mirrored = board(&[(3, 0, 2), (2, 0, 4), (0, 2, 8)]);
+        assert!(network.value(original) > 0.0);
+        assert_eq!(network.value(turned), network.value(original));
+        assert_eq!(network.value(mirrored), network.value(original));
+    }
+
+    #[test]
+    fn saves_and_loads_weights() {
+        let mut network = NTupleNetwork::new(FOUR_TUPLES);
+        let tiles = board(&[(0, 0, 2), (1, 1, 64), (2, 3, 2048)]);
+        network.adjust(tiles, 3.5);
+        let mut bytes = Vec::new();
+        network.write_to(&mut bytes).unwrap();
+
+        let loaded = NTupleNetwork::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(loaded.value(tiles), network.value(tiles));
+        assert_eq!(loaded.tuples.len(), FOUR_TUPLES.len());
+
+        // another version is refused rather than misread
+        bytes[4] += 1;
+        let err = NTupleNetwork::read_from(bytes.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    // a header asking for too many weights is refused before anything is allocated
+    #[test]
+    fn refuses_oversized_layouts() {
+        let header = |tuples: &[&[u8]]| {
+            let mut bytes = MAGIC.to_vec();
+            bytes.extend(FORMAT_VERSION.to_le_bytes());
+            bytes.extend((tuples.len() as u32).to_le_bytes());
+            for cells in tuples {
+                bytes.push(cells.len() as u8);
+                bytes.extend(*cells);
+            }
+            bytes
+        };
+
+        let mut bytes = header(&[]);
+        bytes[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        let err = NTupleNetwork::read_from(bytes.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let six_tuple: &[u8] = &[0, 1, 2, 3, 4, 5];
+        let err = NTupleNetwork::read_from(header(&[six_tuple; 9]).as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,80 @@
+use std::hash::Hash;
+
+use crate::{
+    data::{board_shift::BoardShift, position::Position},
+    engine::{
+        bitboard::{self, Bitboard},
+        grid::Grid,
+    },
+};
+
+/// What the bots need from a board, so 4x4 games can be searched on the much faster
+/// `Bitboard` while every other size falls back to `Grid`.
+pub trait SearchBoard: Clone + Eq + Hash {
+    fn width(&self) -> u8;
+    fn height(&self) -> u8;
+    fn get(&self, pos: Position) -> Option<u32>;
+    /// The board after the shift and the points it scored, or None if nothing moved.
+    fn shifted(&self, shift: BoardShift) -> Option<(Self, u32)>;
+    fn empty_positions(&self) -> Vec<Position>;
+    fn with_tile(&self, pos: Position, value: u32) -> Self;
+}
+
+impl SearchBoard for Grid {
+    fn width(&self) -> u8 {
+        Grid::width(self)
+    }
+
+    fn height(&self) -> u8 {
+        Grid::height(self)
+    }
+
+    fn get(&self, pos: Position) -> Option<u32> {
+        Grid::get(self, pos)
+    }
+
+    fn shifted(&self, shift: BoardShift) -> Option<(Self, u32)> {
+        let mut grid = self.clone();
+        let outcome = grid.apply(shift);
+        outcome.changed().then_some((grid, outcome.score))
+    }
+
+    fn empty_positions(&self) -> Vec<Position> {
+        Grid::empty_positions(self).collect()
+    }
+
+    fn with_tile(&self, pos: Position, value: u32) -> Self {
+        let mut grid = self.clone();
+        grid.set(pos, value);
+        grid
+    }
+}
+
+impl SearchBoard for Bitboard {
+    fn width(&self) -> u8 {
+        bitboard::SIZE
+    }
+
+    fn height(&self) -> u8 {
+        bitboard::SIZE
+    }
+
+    fn get(&self, pos: Position) -> Option<u32> {
+        Bitboard::get(*self, pos)
+    }
+
+    fn shifted(&self, shift: BoardShift) -> Option<(Self, u32)> {
+        let (board, score) = self.apply(shift);
+        (board != *self).then_some((board, score))
+    }
+
+    fn empty_positions(&self) -> Vec<Position> {
+        Bitboard::empty_positions(*self).collect()
+    }
+
+    fn with_tile(&self, pos: Position, value: u32) -> Self {
+        let mut board = *self;
+        board.set(pos, value);
+        board
+    }
+}
@@ -0,0 +1,124 @@
+use std::sync::Arc;
+
+use crate::{
+    data::{board_shift::BoardShift, spawn_rules::SpawnRules},
+    engine::{bitboard::SIZE, grid::Grid},
+};
+
+use super::{expectimax::Expectimax, monte_carlo::MonteCarlo, ntuple::NTupleNetwork};
+
+/// A way of picking moves. Strategies are shared with background tasks, so they have to be
+/// cheap to call from any thread and keep no state between moves.
+pub trait Strategy: Send + Sync {
+    /// The move to make on `grid`, or None when no move changes it.
+    fn choose_move(&self, grid: &Grid) -> Option<BoardShift>;
+}
+
+impl Strategy for Expectimax {
+    fn choose_move(&self, grid: &Grid) -> Option<BoardShift> {
+        self.best_move(grid)
+    }
+}
+
+impl Strategy for MonteCarlo {
+    fn choose_move(&self, grid: &Grid) -> Option<BoardShift> {
+        self.best_move(grid)
+    }
+}
+
+/// Takes whichever move scores the most points right now, a baseline for the smarter bots.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Greedy;
+
+impl Strategy for Greedy {
+    fn choose_move(&self, grid: &Grid) -> Option<BoardShift> {
+        BoardShift::ALL
+            .into_iter()
+            .filter_map(|shift| {
+                let outcome = grid.clone().apply(shift);
+                outcome.changed().then_some((shift, outcome.score))
+            })
+            // the first of several equal moves wins, so ties always break the same way
+            .rev()
+            .max_by_key(|(_, score)| *score)
+            .map(|(shift, _)| shift)
+    }
+}
+
+/// Names accepted by `by_name`, the first one is the default.
+pub const STRATEGY_NAMES: [&str; 4] = ["expectimax", "greedy", "ntuple", "montecarlo"];
+
+/// Fails for a board the strategy called `name` can't play, the n-tuple weights only know
+/// 4x4 boards.
+pub fn check_board_size(name: &str, width: u8, height: u8) -> Result<(), String> {
+    if name == "ntuple" && (width, height) != (SIZE, SIZE) {
+        return Err(format!(
+            "the ntuple strategy only plays {SIZE}x{SIZE} boards, not {width}x{height}"
+        ));
+    }
+    Ok(())
+}
+
+/// The strategy called `name`, looking ahead with the tiles `spawn_rules` spawns. The n-tuple
+/// player needs weights saved by the `train` binary and fails when there are none.
+pub fn by_name(name: &str, spawn_rules: &SpawnRules) -> Result<Arc<dyn Strategy>, String> {
+    match name {
+        "expectimax" => Ok(Arc::new(Expectimax::new(2, spawn_rules.clone()))),
+        "greedy" => Ok(Arc::new(Greedy)),
+        "montecarlo" => Ok(Arc::new(MonteCarlo {
+            spawn_rules: spawn_rules.clone(),
+            ..MonteCarlo::default()
+        })),
+        "ntuple" => {
+            let path = NTupleNetwork::default_path()
+                .ok_or("no data directory to load n-tuple weights from")?;
+            let network = NTupleNetwork::load(&path).map_err(|err| {
+                format!(
+                    "could not load n-tuple weights from {}: {err}, train some with the train binary first",
+                    path.display()
+                )
+            })?;
+            Ok(Arc::new(network))
+        }
+        _ => Err(format!("unknown strategy: {name}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::position::Position;
+
+    #[test]
+    fn greedy_takes_the_most_points() {
+        // left or right merge the 2s, up merges the 8s
+        let tiles = [
+            (Position { x: 0, y: 0 }, 2),
+            (Position { x: 1, y: 0 }, 2),
+            (Position { x: 3, y: 0 }, 8),
+            (Position { x: 3, y: 1 }, 8),
+        ];
+        let grid = Grid::from_tiles(4, 4, tiles);
+        assert_eq!(Greedy.choose_move(&grid), Some(BoardShift::Up));
+    }
+
+    #[test]
+    fn greedy_breaks_ties_in_a_fixed_order() {
+        // left and right both merge the pair, up comes first but scores nothing
+        let tiles = [(Position { x: 1, y: 0 }, 2), (Position { x: 2, y: 0 }, 2)];
+        let grid = Grid::from_tiles(4, 4, tiles);
+        assert_eq!(Greedy.choose_move(&grid), Some(BoardShift::Left));
+
+        // nothing scores, so the first move that changes the board wins
+        let grid = Grid::from_tiles(4, 4, [(Position { x: 0, y: 0 }, 2)]);
+        assert_eq!(Greedy.choose_move(&grid), Some(BoardShift::Up));
+    }
+
+    #[test]
+    fn greedy_finds_no_move_on_a_lost_board() {
+        let tiles = (0..4).flat_map(|y| {
+            (0..4).map(move |x| (Position { x, y }, if (x + y) % 2 == 0 { 2 } else { 4 }))
+        });
+        assert_eq!(Greedy.choose_move(&Grid::from_tiles(4, 4, tiles)), None);
+    }
+}
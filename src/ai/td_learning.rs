@@ -0,0 +1,90 @@
+use rand::Rng;
+
+use crate::{data::spawn_rules::SpawnRules, engine::bitboard::Bitboard};
+
+use super::ntuple::NTupleNetwork;
+
+/// How a training game went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Episode {
+    pub score: u32,
+    pub highest_tile: u32,
+    pub moves: u32,
+}
+
+/// Temporal difference learning over afterstates (Szubert and Jaśkowski, 2014). The network
+/// plays its own best moves, and after every move the value of the previous afterstate is
+/// pulled towards the points of the next move plus the value of the afterstate it leads to,
+/// or towards 0 once the game is over.
+#[derive(Debug, Clone)]
+pub struct TdLearning {
+    pub learning_rate: f32,
+    pub spawn_rules: SpawnRules,
+}
+
+impl Default for TdLearning {
+    fn default() -> Self {
+        TdLearning {
+            learning_rate: 0.1,
+            spawn_rules: SpawnRules::default(),
+        }
+    }
+}
+
+impl TdLearning {
+    /// Plays one game on a 4x4 board, learning as it goes.
+    pub fn play_episode(&self, network: &mut NTupleNetwork, rng: &mut impl Rng) -> Episode {
+        let mut board = Bitboard::default();
+        for _ in 0..self.spawn_rules.starting_tiles {
+            board.spawn(&self.spawn_rules, rng);
+        }
+
+        let mut episode = Episode {
+            score: 0,
+            highest_tile: 0,
+            moves: 0,
+        };
+        let mut previous_afterstate = None;
+        loop {
+            let best = network.best_move(board);
+            if let Some(previous) = previous_afterstate {
+                let target = best.map_or(0.0, |(_, after, points)| {
+                    points as f32 + network.value(after)
+                });
+                let error = target - network.value(previous);
+                network.adjust(previous, self.learning_rate * error);
+            }
+            let Some((_, after, points)) = best else {
+                break;
+            };
+            episode.score += points;
+            episode.moves += 1;
+            previous_afterstate = Some(after);
+            board = after;
+            for _ in 0..self.spawn_rules.tiles_per_move {
+                board.spawn(&self.spawn_rules, rng);
+            }
+        }
+        episode.highest_tile = board.highest_tile().unwrap_or(0);
+        episode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ai::ntuple::FOUR_TUPLES, engine::session::seeded_rng};
+
+    #[test]
+    fn learns_to_score_more() {
+        let mut network = NTupleNetwork::new(FOUR_TUPLES);
+        let training = TdLearning::default();
+        let mut rng = seeded_rng(1);
+        let scores: Vec<u32> = (0..600)
+            .map(|_| training.play_episode(&mut network, &mut rng).score)
+            .collect();
+        let first: u32 = scores[..100].iter().sum();
+        let last: u32 = scores[500..].iter().sum();
+        assert!(last > 2 * first, "{first} -> {last}");
+    }
+}
@@ -0,0 +1,337 @@
+//! Plays the game in a terminal, for machines without a GPU or over SSH.
+//!
+//! `cargo run --release --bin 2048-tui -- --seed 42 --size 5x3 --target 1024`
+//!
+//! Moves go through the same engine as the window, so a seed spawns the same tiles in
+//! both, and finished games count towards the same records.
+
+use std::io::{self, Write};
+
+use boxes::{
+    data::{
+        board_shift::BoardShift,
+        game_settings::GameSettings,
+        launch_options::{parse_number, parse_size, parse_target},
+        position::Position,
+        records::{GameSummary, LeaderboardEntry, Records, RuleSet},
+        spawn_rules::SpawnRules,
+    },
+    engine::session::Session,
+    storage,
+};
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Color, Colors, Print, ResetColor, SetColors},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use rand::random;
+
+// inside of a cell, wide enough for 131072 with some room around it
+const CELL_WIDTH: usize = 8;
+const CELL_HEIGHT: usize = 3;
+
+// a background per tile value, pale pink for 2 through to deep purple like the tiles in the
+// window, anything past the end keeps the last one
+const TILE_BACKGROUNDS: [u8; 12] = [225, 219, 218, 213, 212, 207, 206, 201, 171, 165, 129, 93];
+// tiles from this index on are dark enough to need white text
+const LIGHT_TEXT_FROM: usize = 6;
+const EMPTY_CELL: u8 = 237;
+
+struct Options {
+    seed: Option<u64>,
+    width: u8,
+    height: u8,
+    target_value: u32,
+}
+
+impl Options {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let settings = GameSettings::default();
+        let mut options = Options {
+            seed: None,
+            width: settings.board_width,
+            height: settings.board_height,
+            target_value: settings.target_value,
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{arg} expects a value"));
+            match arg.as_str() {
+                "--seed" => options.seed = Some(parse_number(&arg, &value()?)?),
+                "--size" => (options.width, options.height) = parse_size(&value()?)?,
+                "--target" => options.target_value = parse_target(&value()?)?,
+                _ => return Err(format!("unknown argument: {arg}")),
+            }
+        }
+        Ok(options)
+    }
+}
+
+// the terminal counterpart of Game, MoveHistory and GameRng
+struct TerminalGame {
+    session: Session,
+    seed: u64,
+    started_at: u64,
+    // every move that changed the board, for the stats
+    shifts: Vec<BoardShift>,
+    target_value: u32,
+    won: bool,
+    over: bool,
+    best_score: u32,
+    rules: RuleSet,
+    records: Records,
+    save_error: Option<String>,
+}
+
+impl TerminalGame {
+    fn new(options: &Options) -> Self {
+        let spawn_rules = SpawnRules::default();
+        let rules = RuleSet::new(options.width, options.height, &spawn_rules);
+        let records = Records::load();
+        let seed = options.seed.unwrap_or_else(random);
+        TerminalGame {
+            session: Session::new(options.width, options.height, spawn_rules, seed),
+            seed,
+            started_at: storage::unix_time(),
+            shifts: Vec::new(),
+            target_value: options.target_value,
+            won: false,
+            over: false,
+            best_score: records.best_score(&rules),
+            rules,
+            records,
+            save_error: None,
+        }
+    }
+
+    fn new_game(&mut self, seed: u64) {
+        let grid = &self.session.grid;
+        self.session = Session::new(
+            grid.width(),
+            grid.height(),
+            self.session.spawn_rules.clone(),
+            seed,
+        );
+        self.seed = seed;
+        self.started_at = storage::unix_time();
+        self.shifts.clear();
+        self.won = false;
+        self.over = false;
+        self.save_error = None;
+    }
+
+    fn play(&mut self, board_shift: BoardShift) {
+        if self.over || !self.session.play(board_shift).changed() {
+            return;
+        }
+        self.shifts.push(board_shift);
+        self.best_score = self.best_score.max(self.session.score);
+        if self.highest_tile() >= self.target_value {
+            self.won = true;
+        }
+        if !self.session.grid.has_moves() {
+            self.over = true;
+            self.record();
+        }
+    }
+
+    fn highest_tile(&self) -> u32 {
+        self.session.grid.highest_tile().unwrap_or(0)
+    }
+
+    // the same bookkeeping as record_game
+    fn record(&mut self) {
+        let session = &self.session;
+        let highest_tile = self.highest_tile();
+        let finished_at = storage::unix_time();
+        // the window may have recorded games since this one started, keep those
+        self.records = Records::load();
+        self.records.submit_score(&self.rules, session.score);
+        self.records.submit_to_leaderboard(LeaderboardEntry {
+            score: session.score,
+            highest_tile,
+            moves: session.moves,
+            width: session.grid.width(),
+            height: session.grid.height(),
+            seed: self.seed,
+            started_at: self.started_at,
+            finished_at,
+            // a terminal game is played in one sitting
+            play_time: Some(finished_at.saturating_sub(self.started_at)),
+        });
+        self.records.add_game(&GameSummary {
+            score: session.score,
+            highest_tile,
+            moves: session.moves,
+            won: self.won,
+            shifts: self.shifts.clone(),
+        });
+        if let Err(err) = self.records.save() {
+            self.save_error = Some(format!("could not save records: {err}"));
+        }
+    }
+
+    fn message(&self) -> String {
+        if let Some(err) = self.save_error.as_ref() {
+            err.clone()
+        } else if self.over {
+            "Game over! n starts a new game".to_string()
+        } else if self.won {
+            format!(
+                "You reached {}! Keep going or press n for a new game",
+                self.target_value
+            )
+        } else {
+            String::new()
+        }
+    }
+}
+
+// arrows and WASD, like BoardShift::try_from(&KeyCode)
+fn board_shift(code: KeyCode) -> Option<BoardShift> {
+    match code {
+        KeyCode::Up | KeyCode::Char('w' | 'W') => Some(BoardShift::Up),
+        KeyCode::Down | KeyCode::Char('s' | 'S') => Some(BoardShift::Down),
+        KeyCode::Left | KeyCode::Char('a' | 'A') => Some(BoardShift::Left),
+        KeyCode::Right | KeyCode::Char('d' | 'D') => Some(BoardShift::Right),
+        _ => None,
+    }
+}
+
+// text and background color for a cell
+fn tile_colors(value: Option<u32>) -> Colors {
+    let Some(value) = value else {
+        return Colors::new(Color::White, Color::AnsiValue(EMPTY_CELL));
+    };
+    // 2 is the first color
+    let index = (value.trailing_zeros() as usize)
+        .saturating_sub(1)
+        .min(TILE_BACKGROUNDS.len() - 1);
+    let text = if index < LIGHT_TEXT_FROM {
+        Color::Black
+    } else {
+        Color::White
+    };
+    Colors::new(text, Color::AnsiValue(TILE_BACKGROUNDS[index]))
+}
+
+fn draw(out: &mut impl Write, game: &TerminalGame) -> io::Result<()> {
+    let grid = &game.session.grid;
+    let width = usize::from(grid.width());
+    let horizontal = "─".repeat(CELL_WIDTH);
+    let border = |left: &str, middle: &str, right: &str| {
+        format!(
+            "{left}{}{right}",
+            vec![horizontal.as_str(); width].join(middle)
+        )
+    };
+
+    queue!(
+        out,
+        Clear(ClearType::All),
+        MoveTo(0, 0),
+        Print(format!(
+            "Score {:<8} Best {:<8} Seed {}",
+            game.session.score, game.best_score, game.seed
+        )),
+        MoveTo(0, 2),
+        Print(border("┌", "┬", "┐")),
+    )?;
+    let mut line = 2;
+    // the top row of the board has the highest y, like in the window
+    for y in (0..grid.height()).rev() {
+        for cell_line in 0..CELL_HEIGHT {
+            line += 1;
+            queue!(out, MoveTo(0, line), Print("│"))?;
+            for x in 0..grid.width() {
+                let value = grid.get(Position { x, y });
+                let text = match value {
+                    Some(value) if cell_line == CELL_HEIGHT / 2 => value.to_string(),
+                    _ => String::new(),
+                };
+                queue!(
+                    out,
+                    SetColors(tile_colors(value)),
+                    Print(format!("{text:^CELL_WIDTH$}")),
+                    ResetColor,
+                    Print("│"),
+                )?;
+            }
+        }
+        line += 1;
+        let bottom = if y == 0 {
+            border("└", "┴", "┘")
+        } else {
+            border("├", "┼", "┤")
+        };
+        queue!(out, MoveTo(0, line), Print(bottom))?;
+    }
+    queue!(
+        out,
+        MoveTo(0, line + 2),
+        Print(game.message()),
+        MoveTo(0, line + 3),
+        Print("arrows or WASD to move, n for a new game, q to quit"),
+    )?;
+    out.flush()
+}
+
+// raw mode on the alternate screen until dropped, so the shell is left as it was
+// even when the game panics
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(RawTerminal)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn run(options: &Options) -> io::Result<()> {
+    let mut game = TerminalGame::new(options);
+    let _terminal = RawTerminal::enter()?;
+    let mut out = io::stdout().lock();
+    loop {
+        draw(&mut out, &game)?;
+        // anything else, like the terminal being resized, just draws the board again
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        else {
+            continue;
+        };
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Char('n') => game.new_game(random()),
+            code => {
+                if let Some(board_shift) = board_shift(code) {
+                    game.play(board_shift);
+                }
+            }
+        }
+    }
+}
+
+fn main() {
+    let options = Options::from_args(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+    if let Err(err) = run(&options) {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,271 @@
+//! Plays a batch of games with one of the bots, without a window, and reports how they went.
+//!
+//! `cargo run --release --bin simulate -- --strategy expectimax --games 1000 --seed 0`
+//!
+//! Games are spread over every core. Each one is played from its own seed (`--seed`,
+//! `--seed + 1`, ...) with the same rules as the game, so a run can be repeated exactly and
+//! any single game can be watched again with `boxes --seed`. `--json` prints the report,
+//! including every game, as JSON instead of text.
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Instant,
+};
+
+use boxes::{
+    ai::strategy::{self, Strategy, STRATEGY_NAMES},
+    data::{
+        game_settings::GameSettings,
+        launch_options::{parse_number, parse_size, parse_target},
+        spawn_rules::SpawnRules,
+    },
+    engine::session::Session,
+};
+use serde::Serialize;
+
+struct Options {
+    strategy: String,
+    games: u64,
+    first_seed: u64,
+    width: u8,
+    height: u8,
+    target_value: u32,
+    threads: usize,
+    json: bool,
+}
+
+impl Options {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let settings = GameSettings::default();
+        let mut options = Options {
+            strategy: STRATEGY_NAMES[0].to_string(),
+            games: 100,
+            first_seed: 0,
+            width: settings.board_width,
+            height: settings.board_height,
+            target_value: settings.target_value,
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+            json: false,
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{arg} expects a value"));
+            match arg.as_str() {
+                "--strategy" => {
+                    let value = value()?;
+                    if !STRATEGY_NAMES.contains(&value.as_str()) {
+                        let names = STRATEGY_NAMES.join(", ");
+                        return Err(format!(
+                            "unknown strategy: {value}, expected one of {names}"
+                        ));
+                    }
+                    options.strategy = value;
+                }
+                "--games" => options.games = parse_number(&arg, &value()?)?,
+                "--seed" => options.first_seed = parse_number(&arg, &value()?)?,
+                "--size" => (options.width, options.height) = parse_size(&value()?)?,
+                "--target" => options.target_value = parse_target(&value()?)?,
+                "--threads" => options.threads = parse_number::<usize>(&arg, &value()?)?.max(1),
+                "--json" => options.json = true,
+                _ => return Err(format!("unknown argument: {arg}")),
+            }
+        }
+        strategy::check_board_size(&options.strategy, options.width, options.height)?;
+        // the games are seeded first_seed, first_seed + 1 and so on
+        if options
+            .first_seed
+            .checked_add(options.games.saturating_sub(1))
+            .is_none()
+        {
+            return Err(format!(
+                "--seed {} with --games {} runs past the largest seed, {}",
+                options.first_seed,
+                options.games,
+                u64::MAX
+            ));
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct GameResult {
+    seed: u64,
+    score: u32,
+    highest_tile: u32,
+    moves: u32,
+}
+
+fn play(strategy: &dyn Strategy, options: &Options, seed: u64) -> GameResult {
+    let mut session = Session::new(options.width, options.height, SpawnRules::default(), seed);
+    while let Some(board_shift) = strategy.choose_move(&session.grid) {
+        // a strategy that picks a move going nowhere would otherwise never finish
+        if !session.play(board_shift).changed() {
+            break;
+        }
+    }
+    GameResult {
+        seed,
+        score: session.score,
+        highest_tile: session.grid.highest_tile().unwrap_or(0),
+        moves: session.moves,
+    }
+}
+
+// every game, sorted by seed; each thread takes the next seed as soon as it's free
+fn play_all(strategy: &dyn Strategy, options: &Options) -> Vec<GameResult> {
+    let next_game = AtomicU64::new(0);
+    let results = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for _ in 0..options.threads {
+            scope.spawn(|| loop {
+                let game = next_game.fetch_add(1, Ordering::Relaxed);
+                if game >= options.games {
+                    break;
+                }
+                let result = play(strategy, options, options.first_seed + game);
+                results.lock().unwrap().push(result);
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|result| result.seed);
+    results
+}
+
+#[derive(Debug, Serialize)]
+struct ScoreSummary {
+    min: u32,
+    p25: u32,
+    median: u32,
+    p75: u32,
+    p90: u32,
+    max: u32,
+    mean: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    strategy: String,
+    width: u8,
+    height: u8,
+    target_value: u32,
+    first_seed: u64,
+    games: usize,
+    wins: usize,
+    win_rate: f64,
+    score: ScoreSummary,
+    mean_moves: f64,
+    // how many games ended with each highest tile
+    highest_tiles: BTreeMap<u32, usize>,
+    seconds: f64,
+    results: Vec<GameResult>,
+}
+
+impl Report {
+    fn new(options: &Options, results: Vec<GameResult>, seconds: f64) -> Self {
+        let games = results.len();
+        let mut scores: Vec<u32> = results.iter().map(|result| result.score).collect();
+        scores.sort_unstable();
+        // nearest rank, good enough to see how a strategy is spread
+        let percentile = |p: usize| match games {
+            0 => 0,
+            _ => scores[(p * games).div_ceil(100).clamp(1, games) - 1],
+        };
+        let mean = |total: u64| match games {
+            0 => 0.0,
+            _ => total as f64 / games as f64,
+        };
+
+        let wins = results
+            .iter()
+            .filter(|result| result.highest_tile >= options.target_value)
+            .count();
+        let mut highest_tiles = BTreeMap::new();
+        for result in results.iter() {
+            *highest_tiles.entry(result.highest_tile).or_default() += 1;
+        }
+
+        Report {
+            strategy: options.strategy.clone(),
+            width: options.width,
+            height: options.height,
+            target_value: options.target_value,
+            first_seed: options.first_seed,
+            games,
+            wins,
+            win_rate: match games {
+                0 => 0.0,
+                _ => wins as f64 / games as f64,
+            },
+            score: ScoreSummary {
+                min: scores.first().copied().unwrap_or(0),
+                p25: percentile(25),
+                median: percentile(50),
+                p75: percentile(75),
+                p90: percentile(90),
+                max: scores.last().copied().unwrap_or(0),
+                mean: mean(scores.iter().map(|&score| u64::from(score)).sum()),
+            },
+            mean_moves: mean(results.iter().map(|result| u64::from(result.moves)).sum()),
+            highest_tiles,
+            seconds,
+            results,
+        }
+    }
+
+    fn print_text(&self) {
+        println!(
+            "{} on {}x{}, {} games from seed {} in {:.1}s",
+            self.strategy, self.width, self.height, self.games, self.first_seed, self.seconds
+        );
+        println!(
+            "win rate  {:.1}% ({} reached {})",
+            self.win_rate * 100.0,
+            self.wins,
+            self.target_value
+        );
+        let score = &self.score;
+        println!(
+            "score     min {}  p25 {}  median {}  p75 {}  p90 {}  max {}  mean {:.0}",
+            score.min, score.p25, score.median, score.p75, score.p90, score.max, score.mean
+        );
+        println!("moves     mean {:.0}", self.mean_moves);
+        println!("highest tile");
+        let most = self.highest_tiles.values().copied().max().unwrap_or(0);
+        for (tile, count) in self.highest_tiles.iter() {
+            let share = *count as f64 / self.games as f64;
+            let bar = "#".repeat((40 * count).div_ceil(most));
+            println!("{tile:>8} {count:>7} {:>6.1}% {bar}", share * 100.0);
+        }
+    }
+}
+
+fn main() {
+    let options = Options::from_args(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+    let strategy =
+        strategy::by_name(&options.strategy, &SpawnRules::default()).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            std::process::exit(2);
+        });
+
+    let started = Instant::now();
+    let results = play_all(strategy.as_ref(), &options);
+    let report = Report::new(&options, results, started.elapsed().as_secs_f64());
+
+    if options.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).expect("the report is plain data")
+        );
+    } else {
+        report.print_text();
+    }
+}
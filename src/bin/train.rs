@@ -0,0 +1,155 @@
+//! Trains an n-tuple network by playing against itself, on the CPU.
+//!
+//! `cargo run --release --bin train -- --games 100000`
+//!
+//! The weights are saved to `ntuple.weights` in the data directory, where the `ntuple`
+//! strategy picks them up, or to `--output`. `--resume` carries on from the weights already
+//! there, with the tuples they were trained with. Every `--report-every` games it prints how the latest games went, and the weights
+//! are saved every `--save-every` games and at the end.
+
+use std::{path::PathBuf, time::Instant};
+
+use boxes::{
+    ai::{
+        ntuple::{NTupleNetwork, FOUR_TUPLES, SIX_TUPLES},
+        td_learning::{Episode, TdLearning},
+    },
+    data::launch_options::parse_number,
+    engine::session::seeded_rng,
+};
+
+struct Options {
+    games: u64,
+    // None trains SIX_TUPLES, a resumed network keeps the tuples it was saved with
+    tuples: Option<&'static [&'static [u8]]>,
+    learning_rate: f32,
+    seed: u64,
+    output: Option<PathBuf>,
+    resume: bool,
+    report_every: u64,
+    save_every: u64,
+}
+
+impl Options {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options {
+            games: 100_000,
+            tuples: None,
+            learning_rate: TdLearning::default().learning_rate,
+            seed: 0,
+            output: None,
+            resume: false,
+            report_every: 1000,
+            save_every: 10_000,
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("{arg} expects a value"));
+            match arg.as_str() {
+                "--games" => options.games = parse_number(&arg, &value()?)?,
+                "--tuples" => {
+                    options.tuples = match value()?.as_str() {
+                        "4" => Some(FOUR_TUPLES),
+                        "6" => Some(SIX_TUPLES),
+                        other => return Err(format!("--tuples expects 4 or 6, got {other}")),
+                    }
+                }
+                "--learning-rate" => {
+                    let value = value()?;
+                    options.learning_rate = value
+                        .parse()
+                        .ok()
+                        .filter(|rate: &f32| rate.is_finite() && *rate > 0.0)
+                        .ok_or_else(|| format!("invalid learning rate: {value}"))?;
+                }
+                "--seed" => options.seed = parse_number(&arg, &value()?)?,
+                "--output" => options.output = Some(PathBuf::from(value()?)),
+                "--resume" => options.resume = true,
+                "--report-every" => {
+                    options.report_every = parse_number::<u64>(&arg, &value()?)?.max(1)
+                }
+                "--save-every" => options.save_every = parse_number::<u64>(&arg, &value()?)?.max(1),
+                _ => return Err(format!("unknown argument: {arg}")),
+            }
+        }
+        if options.resume && options.tuples.is_some() {
+            return Err(
+                "--resume keeps the tuples of the saved weights, drop --tuples".to_string(),
+            );
+        }
+        Ok(options)
+    }
+}
+
+// the games since the last report
+fn report(games: u64, recent: &[Episode], started: Instant) {
+    let count = recent.len().max(1);
+    let mean_score = recent
+        .iter()
+        .map(|episode| u64::from(episode.score))
+        .sum::<u64>()
+        / count as u64;
+    let max_score = recent
+        .iter()
+        .map(|episode| episode.score)
+        .max()
+        .unwrap_or(0);
+    let share_reaching = |tile: u32| {
+        let reached = recent
+            .iter()
+            .filter(|episode| episode.highest_tile >= tile)
+            .count();
+        100.0 * reached as f64 / count as f64
+    };
+    println!(
+        "{games:>9} games  mean {mean_score:>7}  max {max_score:>7}  2048 {:>5.1}%  8192 {:>5.1}%  {:.0} games/s",
+        share_reaching(2048),
+        share_reaching(8192),
+        games as f64 / started.elapsed().as_secs_f64(),
+    );
+}
+
+fn main() {
+    let options = Options::from_args(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+    let Some(path) = options.output.clone().or_else(NTupleNetwork::default_path) else {
+        eprintln!("no data directory to save the weights to, pass --output");
+        std::process::exit(2);
+    };
+
+    let mut network = if options.resume {
+        NTupleNetwork::load(&path).unwrap_or_else(|err| {
+            eprintln!("could not load {}: {err}", path.display());
+            std::process::exit(2);
+        })
+    } else {
+        NTupleNetwork::new(options.tuples.unwrap_or(SIX_TUPLES))
+    };
+    let save = |network: &NTupleNetwork| {
+        if let Err(err) = network.save(&path) {
+            eprintln!("could not save {}: {err}", path.display());
+            std::process::exit(1);
+        }
+    };
+
+    let training = TdLearning {
+        learning_rate: options.learning_rate,
+        ..Default::default()
+    };
+    let mut rng = seeded_rng(options.seed);
+    let mut recent = Vec::new();
+    let started = Instant::now();
+    for game in 1..=options.games {
+        recent.push(training.play_episode(&mut network, &mut rng));
+        if game % options.report_every == 0 {
+            report(game, &recent, started);
+            recent.clear();
+        }
+        if game % options.save_every == 0 {
+            save(&network);
+        }
+    }
+    save(&network);
+    println!("saved the weights to {}", path.display());
+}
@@ -0,0 +1,184 @@
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use futures_lite::future;
+
+use crate::{
+    data::{
+        autoplay::Autoplay, board::Board, board_shift::BoardShift,
+        board_shift_event::BoardShiftEvent, game::Game, points::Points, position::Position,
+        screen::Screen,
+    },
+    engine::grid::Grid,
+    systems::board_shift::board_shift,
+    RunState,
+};
+
+// Runs the autoplay strategy on the AsyncComputeTaskPool, so a deep search doesn't hold up
+// rendering or the tile animations while it thinks.
+pub struct BotPlugin;
+
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (
+                // the other screens cover the board, and the stats they show only change
+                // when a game ends
+                request_move.run_if(in_state(Screen::Board)),
+                receive_move.before(board_shift),
+            )
+                .in_set(OnUpdate(RunState::Playing)),
+        )
+        .add_system(cancel_moves.in_schedule(OnExit(RunState::Playing)));
+    }
+}
+
+// a move being worked out for the board in `grid`, dropping the task cancels it
+#[derive(Component)]
+struct PendingMove {
+    task: Task<Option<BoardShift>>,
+    grid: Grid,
+}
+
+fn current_grid(board: &Board, tiles: &Query<(&Position, &Points)>) -> Grid {
+    Grid::from_tiles(
+        board.width,
+        board.height,
+        tiles.iter().map(|(pos, points)| (*pos, points.value)),
+    )
+}
+
+fn request_move(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut autoplay: ResMut<Autoplay>,
+    tiles: Query<(&Position, &Points)>,
+    query_board: Query<&Board>,
+    pending: Query<(), With<PendingMove>>,
+) {
+    // one move at a time, a slow strategy sets its own pace. Ticking the timer isn't a
+    // change the autoplay button needs to hear about.
+    if !autoplay.enabled
+        || !autoplay
+            .bypass_change_detection()
+            .timer
+            .tick(time.delta())
+            .just_finished()
+        || !pending.is_empty()
+    {
+        return;
+    }
+
+    let grid = current_grid(query_board.single(), &tiles);
+    let strategy = autoplay.strategy.clone();
+    let task_grid = grid.clone();
+    let task = AsyncComputeTaskPool::get().spawn(async move { strategy.choose_move(&task_grid) });
+    commands.spawn(PendingMove { task, grid });
+}
+
+// sends a finished move down the same path as a key press, so the tiles animate as usual
+fn receive_move(
+    mut commands: Commands,
+    mut pending: Query<(Entity, &mut PendingMove)>,
+    tiles: Query<(&Position, &Points)>,
+    query_board: Query<&Board>,
+    autoplay: Res<Autoplay>,
+    mut game: ResMut<Game>,
+    mut shift_writer: EventWriter<BoardShiftEvent>,
+) {
+    for (entity, mut pending_move) in pending.iter_mut() {
+        // a key press or an undo since the task started means it's working on the wrong board
+        if !autoplay.enabled || pending_move.grid != current_grid(query_board.single(), &tiles) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let Some(choice) = future::block_on(future::poll_once(&mut pending_move.task)) else {
+            continue;
+        };
+        commands.entity(entity).despawn();
+        if let Some(board_shift) = choice {
+            game.autoplayed = true;
+            shift_writer.send(BoardShiftEvent { board_shift });
+        }
+    }
+}
+
+fn cancel_moves(mut commands: Commands, pending: Query<Entity, With<PendingMove>>) {
+    for entity in pending.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::ai::strategy::Greedy;
+
+    // an app with autoplay on and a board holding a pair of 2s, left merges them
+    fn app() -> App {
+        let mut app = App::new();
+        let mut autoplay = Autoplay::new(4.0, Arc::new(Greedy));
+        autoplay.enabled = true;
+        app.add_plugins(MinimalPlugins)
+            .add_event::<BoardShiftEvent>()
+            .insert_resource(autoplay)
+            .init_resource::<Game>()
+            .add_system(receive_move);
+        app.world.spawn(Board::new(4, 4));
+        for x in [1, 2] {
+            app.world.spawn((Position { x, y: 0 }, Points { value: 2 }));
+        }
+        app
+    }
+
+    fn pending_move(app: &mut App, grid: Grid) {
+        let task = AsyncComputeTaskPool::get().spawn(async { Some(BoardShift::Left) });
+        app.world.spawn(PendingMove { task, grid });
+    }
+
+    fn sent_moves(app: &App) -> Vec<BoardShift> {
+        let events = app.world.resource::<Events<BoardShiftEvent>>();
+        let mut reader = events.get_reader();
+        reader.iter(events).map(|event| event.board_shift).collect()
+    }
+
+    // updates until the pending move is gone, answered or dropped
+    fn settle(app: &mut App) {
+        for _ in 0..1000 {
+            app.update();
+            let mut pending = app.world.query::<&PendingMove>();
+            if pending.iter(&app.world).next().is_none() {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        panic!("the move never arrived");
+    }
+
+    #[test]
+    fn plays_a_move_worked_out_for_this_board() {
+        let mut app = app();
+        let tiles = [(Position { x: 1, y: 0 }, 2), (Position { x: 2, y: 0 }, 2)];
+        pending_move(&mut app, Grid::from_tiles(4, 4, tiles));
+        settle(&mut app);
+
+        assert_eq!(sent_moves(&app), vec![BoardShift::Left]);
+        assert!(app.world.resource::<Game>().autoplayed);
+    }
+
+    #[test]
+    fn drops_a_move_worked_out_for_another_board() {
+        let mut app = app();
+        // the board before a key press moved the 2s
+        let tiles = [(Position { x: 2, y: 0 }, 2), (Position { x: 3, y: 0 }, 2)];
+        pending_move(&mut app, Grid::from_tiles(4, 4, tiles));
+        settle(&mut app);
+
+        assert!(sent_moves(&app).is_empty());
+        assert!(!app.world.resource::<Game>().autoplayed);
+    }
+}
@@ -28,6 +28,14 @@ pub const SCORE_BOX: Color = Color::Lcha {
     alpha: 1.0,
 };
 
+// dims the board behind the win screen
+pub const OVERLAY: Color = Color::Lcha {
+    lightness: 0.06,
+    chroma: 0.088,
+    hue: 281.0,
+    alpha: 0.85,
+};
+
 pub mod button {
     use bevy::prelude::Color;
 
@@ -0,0 +1,6 @@
+use super::achievements::Achievement;
+
+// sent once for every newly unlocked achievement
+pub struct AchievementUnlockedEvent {
+    pub achievement: &'static Achievement,
+}
@@ -0,0 +1,183 @@
+use std::{collections::HashSet, io, path::PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+use super::board_shift::BoardShift;
+
+// what has to happen in a single game to unlock an achievement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    ReachTile(u32),
+    Score(u32),
+    // reach the score without making more than this many moves
+    ScoreWithin { score: u32, moves: u32 },
+    // reach the target tile without ever moving in one of the four directions
+    WinWithoutDirection,
+    WinOnBoard { width: u8, height: u8 },
+    // run out of moves within this many moves
+    GameOverWithin(u32),
+}
+
+pub struct Achievement {
+    // stored in achievements.json, so it must never change once released
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub condition: Condition,
+}
+
+// Adding an achievement only takes a new entry here, the list screen picks it up as well
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "tile_512",
+        name: "Getting there",
+        description: "Make a 512 tile",
+        condition: Condition::ReachTile(512),
+    },
+    Achievement {
+        id: "tile_1024",
+        name: "Halfway",
+        description: "Make a 1024 tile",
+        condition: Condition::ReachTile(1024),
+    },
+    Achievement {
+        id: "tile_2048",
+        name: "2048",
+        description: "Make a 2048 tile",
+        condition: Condition::ReachTile(2048),
+    },
+    Achievement {
+        id: "tile_4096",
+        name: "Beyond",
+        description: "Make a 4096 tile",
+        condition: Condition::ReachTile(4096),
+    },
+    Achievement {
+        id: "score_10000",
+        name: "High scorer",
+        description: "Score 10000 points in one game",
+        condition: Condition::Score(10_000),
+    },
+    Achievement {
+        id: "quick_1000",
+        name: "In a hurry",
+        description: "Score 1000 points within 100 moves",
+        condition: Condition::ScoreWithin {
+            score: 1000,
+            moves: 100,
+        },
+    },
+    Achievement {
+        id: "three_directions",
+        name: "Three ways",
+        description: "Win without moving in one of the four directions",
+        condition: Condition::WinWithoutDirection,
+    },
+    Achievement {
+        id: "win_3x3",
+        name: "Tight squeeze",
+        description: "Win on a 3x3 board",
+        condition: Condition::WinOnBoard {
+            width: 3,
+            height: 3,
+        },
+    },
+    Achievement {
+        id: "gridlock",
+        name: "Gridlock",
+        description: "Run out of moves within 100 moves",
+        condition: Condition::GameOverWithin(100),
+    },
+];
+
+// the state of the game at the time of a GameEvent
+pub struct GameProgress {
+    pub width: u8,
+    pub height: u8,
+    pub score: u32,
+    pub moves: u32,
+    pub highest_tile: u32,
+    pub target_value: u32,
+    pub shifts_used: HashSet<BoardShift>,
+    pub game_over: bool,
+}
+
+impl Condition {
+    pub fn is_met(&self, progress: &GameProgress) -> bool {
+        let won = progress.highest_tile >= progress.target_value;
+        match *self {
+            Condition::ReachTile(value) => progress.highest_tile >= value,
+            Condition::Score(score) => progress.score >= score,
+            Condition::ScoreWithin { score, moves } => {
+                progress.score >= score && progress.moves <= moves
+            }
+            Condition::WinWithoutDirection => won && progress.shifts_used.len() < 4,
+            Condition::WinOnBoard { width, height } => {
+                won && progress.width == width && progress.height == height
+            }
+            Condition::GameOverWithin(moves) => progress.game_over && progress.moves <= moves,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnlockedAchievement {
+    pub id: String,
+    // seconds since the Unix epoch
+    pub unlocked_at: u64,
+}
+
+// Unlocked achievements, kept in achievements.json in the data directory
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Achievements {
+    pub unlocked: Vec<UnlockedAchievement>,
+}
+
+impl Achievements {
+    fn path() -> Option<PathBuf> {
+        storage::data_dir().map(|dir| dir.join("achievements.json"))
+    }
+
+    // a missing or unreadable file starts with nothing unlocked
+    pub fn load() -> Self {
+        let Some(path) = Achievements::path() else {
+            return Achievements::default();
+        };
+        match storage::load_json(&path) {
+            Ok(achievements) => achievements,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Achievements::default(),
+            Err(err) => {
+                warn!("could not read {}: {err}", path.display());
+                Achievements::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        match Achievements::path() {
+            Some(path) => storage::save_json(&path, self),
+            None => Ok(()),
+        }
+    }
+
+    // when the achievement was unlocked, None while it's still locked
+    pub fn unlocked_at(&self, id: &str) -> Option<u64> {
+        self.unlocked
+            .iter()
+            .find(|unlocked| unlocked.id == id)
+            .map(|unlocked| unlocked.unlocked_at)
+    }
+
+    pub fn unlock(&mut self, id: &str) {
+        if self.unlocked_at(id).is_none() {
+            self.unlocked.push(UnlockedAchievement {
+                id: id.to_string(),
+                unlocked_at: storage::unix_time(),
+            });
+        }
+    }
+}
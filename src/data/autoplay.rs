@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::ai::strategy::Strategy;
+
+pub const DEFAULT_MOVES_PER_SECOND: f32 = 4.0;
+
+// The game playing itself, switched on and off with P or the Autoplay button
+#[derive(Resource)]
+pub struct Autoplay {
+    pub enabled: bool,
+    // picks the moves, see BotPlugin
+    pub strategy: Arc<dyn Strategy>,
+    // one move every time this finishes
+    pub timer: Timer,
+}
+
+impl Autoplay {
+    pub fn new(moves_per_second: f32, strategy: Arc<dyn Strategy>) -> Self {
+        Autoplay {
+            enabled: false,
+            strategy,
+            timer: Timer::from_seconds(1.0 / moves_per_second, TimerMode::Repeating),
+        }
+    }
+}
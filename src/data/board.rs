@@ -2,31 +2,41 @@ use bevy::prelude::*;
 
 use crate::systems::spawn_board::{TILE_SIZE, TILE_SPACER};
 
+use super::position::Position;
+
 #[derive(Component)]
 pub struct Board {
-    pub size: u8,
-    pub physical_size: f32,
+    pub width: u8,
+    pub height: u8,
+    pub physical_size: Vec2,
 }
 
 impl Board {
-    pub fn new(size: u8) -> Self {
-        let physical_size = f32::from(size) * TILE_SIZE + f32::from(size + 1) * TILE_SPACER;
+    pub fn new(width: u8, height: u8) -> Self {
+        let physical_size = Vec2::new(physical_length(width), physical_length(height));
         Board {
-            size,
+            width,
+            height,
             physical_size,
         }
     }
-    pub fn cell_position_to_physical(&self, pos: u8) -> f32 {
+    pub fn cell_position_to_physical(&self, pos: Position) -> Vec2 {
         // the offset is the starting point for drawing a tile
-        // we use the iterator values + the offset to draw each tile
-        let offset = (-self.physical_size / 2.0) // move to the far left
-            + (TILE_SIZE / 2.0); // move 1/2 of a tile to the right
+        // we use the cell coordinates + the offset to draw each tile
+        let offset = (-self.physical_size / 2.0) // move to the bottom left corner
+            + (TILE_SIZE / 2.0); // move 1/2 of a tile up and to the right
+        let cell = Vec2::new(f32::from(pos.x), f32::from(pos.y));
 
         offset
-        + (f32::from(pos) * TILE_SIZE) // add x/y coord offset
-        + (f32::from(pos + 1) * TILE_SPACER) // add spacer offset
+        + (cell * TILE_SIZE) // add x/y coord offset
+        + ((cell + 1.0) * TILE_SPACER) // add spacer offset
     }
     pub fn size(&self) -> Vec2 {
-        Vec2::new(self.physical_size, self.physical_size)
+        self.physical_size
     }
 }
+
+// tiles plus the spacers between and around them along one axis
+fn physical_length(cells: u8) -> f32 {
+    f32::from(cells) * TILE_SIZE + f32::from(cells + 1) * TILE_SPACER
+}
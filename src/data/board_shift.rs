@@ -1,9 +1,10 @@
-use std::cmp::Ordering;
-
+#[cfg(feature = "gui")]
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use super::position::Position;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BoardShift {
     Up,
     Down,
@@ -12,51 +13,51 @@ pub enum BoardShift {
 }
 
 impl BoardShift {
-    pub fn sort(&self, a: &Position, b: &Position) -> Ordering {
+    // every direction, in a fixed order, e.g. for numbering the actions of an agent
+    pub const ALL: [BoardShift; 4] = [
+        BoardShift::Up,
+        BoardShift::Down,
+        BoardShift::Left,
+        BoardShift::Right,
+    ];
+
+    // how many rows (perpendicular to the shift) there are and how many cells each has
+    pub fn rows(&self, board_width: u8, board_height: u8) -> (u8, u8) {
         match self {
-            BoardShift::Up => match Ord::cmp(&b.x, &a.x) {
-                Ordering::Equal => Ord::cmp(&b.y, &a.y),
-                ordering => ordering,
-            },
-            BoardShift::Down => match Ord::cmp(&a.x, &b.x) {
-                Ordering::Equal => Ord::cmp(&a.y, &b.y),
-                ordering => ordering,
-            },
-            BoardShift::Left => match Ord::cmp(&a.y, &b.y) {
-                Ordering::Equal => Ord::cmp(&a.x, &b.x),
-                ordering => ordering,
-            },
-            BoardShift::Right => match Ord::cmp(&b.y, &a.y) {
-                Ordering::Equal => Ord::cmp(&b.x, &a.x),
-                ordering => ordering,
-            },
+            BoardShift::Up | BoardShift::Down => (board_width, board_height),
+            BoardShift::Left | BoardShift::Right => (board_height, board_width),
         }
     }
-    pub fn set_column_position(&self, board_size: u8, position: &mut Mut<Position>, index: u8) {
+    // map a row and an index counted from the edge the tiles are pushed against
+    // to a cell on the board
+    pub fn cell_position(&self, board_width: u8, board_height: u8, row: u8, index: u8) -> Position {
         match self {
-            BoardShift::Up => {
-                position.y = board_size - 1 - index;
-            }
-            BoardShift::Down => {
-                position.y = index;
-            }
-            BoardShift::Left => {
-                position.x = index;
-            }
-            BoardShift::Right => {
-                position.x = board_size - 1 - index;
-            }
+            BoardShift::Up => Position {
+                x: row,
+                y: board_height - 1 - index,
+            },
+            BoardShift::Down => Position { x: row, y: index },
+            BoardShift::Left => Position { x: index, y: row },
+            BoardShift::Right => Position {
+                x: board_width - 1 - index,
+                y: row,
+            },
         }
     }
-    pub fn get_row_position(&self, position: &Position) -> u8 {
+    // unit vector pointing the way the tiles are pushed, in world space
+    #[cfg(feature = "gui")]
+    pub fn direction(&self) -> Vec2 {
         match self {
-            BoardShift::Up | BoardShift::Down => position.x,
-            BoardShift::Left | BoardShift::Right => position.y,
+            BoardShift::Up => Vec2::Y,
+            BoardShift::Down => Vec2::NEG_Y,
+            BoardShift::Left => Vec2::NEG_X,
+            BoardShift::Right => Vec2::X,
         }
     }
 }
 
 // impl TryFrom trait for shared reference to a key code
+#[cfg(feature = "gui")]
 impl TryFrom<&KeyCode> for BoardShift {
     type Error = &'static str;
     fn try_from(value: &KeyCode) -> Result<Self, Self::Error> {
@@ -0,0 +1,6 @@
+use super::board_shift::BoardShift;
+
+// a move to play, from the keyboard or from redo
+pub struct BoardShiftEvent {
+    pub board_shift: BoardShift,
+}
@@ -1,7 +1,27 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 
 #[derive(Default, Resource)]
 pub struct Game {
     pub score: u32,
+    // best score for the current board size and spawn rules, see Records
     pub best_score: u32,
+    pub moves: u32,
+    // when the game started, in seconds since the Unix epoch
+    pub started_at: u64,
+    // time spent in RunState::Playing looking at the board, saved along with the game
+    pub play_time: Duration,
+    // the target tile was reached during this game
+    pub won: bool,
+    // set when going back to RunState::Playing should continue the current game
+    // instead of starting a new one
+    pub resuming: bool,
+    // autoplay made at least one of the moves, so the game doesn't count towards
+    // the records or achievements
+    pub autoplayed: bool,
+    // this ending of the game was recorded and its replay written
+    pub recorded: bool,
+    // the game already ended once and was added to the lifetime stats
+    pub in_stats: bool,
 }
@@ -0,0 +1,9 @@
+// Things that happened in a live game, for systems that react to progress rather than
+// to the board itself. Replays don't send these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    // a move changed the board, sent by board_shift
+    Moved { highest_tile: u32 },
+    // no moves are left, sent by end_game
+    GameOver { highest_tile: u32 },
+}
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+use rand_pcg::Pcg64;
+
+use crate::engine::session::seeded_rng;
+
+// Every tile spawn draws from this resource, so the same seed plus the same moves
+// always plays out the same game.
+#[derive(Resource)]
+pub struct GameRng {
+    pub seed: u64,
+    pub rng: Pcg64,
+    // seed requested at launch or by a replay, only used for the next game
+    next_seed: Option<u64>,
+}
+
+impl GameRng {
+    pub fn new(launch_seed: Option<u64>) -> Self {
+        let seed = launch_seed.unwrap_or_else(random);
+        GameRng {
+            seed,
+            rng: seeded_rng(seed),
+            next_seed: launch_seed,
+        }
+    }
+
+    pub fn set_next_seed(&mut self, seed: u64) {
+        self.next_seed = Some(seed);
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = seeded_rng(seed);
+    }
+
+    // pick the seed for a fresh game, a requested seed wins over a random one
+    pub fn new_game(&mut self) {
+        let seed = self.next_seed.take().unwrap_or_else(random);
+        self.reseed(seed);
+    }
+}
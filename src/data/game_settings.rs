@@ -0,0 +1,44 @@
+#[cfg(feature = "gui")]
+use bevy::prelude::*;
+
+pub const MIN_BOARD_SIZE: u8 = 3;
+pub const MAX_BOARD_SIZE: u8 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardDimension {
+    Width,
+    Height,
+}
+
+// Settings picked in the new game menu, applied the next time a game starts
+#[cfg_attr(feature = "gui", derive(Resource))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameSettings {
+    pub board_width: u8,
+    pub board_height: u8,
+    // reaching a tile with this value wins the game
+    pub target_value: u32,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        GameSettings {
+            board_width: 4,
+            board_height: 4,
+            target_value: 2048,
+        }
+    }
+}
+
+impl GameSettings {
+    // step one side of the board up or down, staying within the supported range
+    pub fn change_board_size(&mut self, dimension: BoardDimension, delta: i8) {
+        let size = match dimension {
+            BoardDimension::Width => &mut self.board_width,
+            BoardDimension::Height => &mut self.board_height,
+        };
+        *size = size
+            .saturating_add_signed(delta)
+            .clamp(MIN_BOARD_SIZE, MAX_BOARD_SIZE);
+    }
+}
@@ -0,0 +1,6 @@
+use super::board_shift::BoardShift;
+
+// sent when a shift doesn't move or merge any tile
+pub struct InvalidMoveEvent {
+    pub board_shift: BoardShift,
+}
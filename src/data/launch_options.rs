@@ -0,0 +1,147 @@
+use std::{path::PathBuf, str::FromStr};
+
+#[cfg(feature = "gui")]
+use bevy::prelude::*;
+
+use crate::ai::strategy::{self, STRATEGY_NAMES};
+
+use super::game_settings::{GameSettings, MAX_BOARD_SIZE, MIN_BOARD_SIZE};
+#[cfg(feature = "gui")]
+use super::{
+    autoplay::{Autoplay, DEFAULT_MOVES_PER_SECOND},
+    spawn_rules::SpawnRules,
+};
+
+// Options passed on the command line, e.g. `boxes --seed 42 --size 5x3 --target 1024`,
+// `boxes --replay path/to/replay.json` or `boxes --autoplay-rate 10 --strategy greedy`
+#[cfg_attr(feature = "gui", derive(Resource))]
+#[derive(Default, Debug)]
+pub struct LaunchOptions {
+    pub seed: Option<u64>,
+    // width and height
+    pub board_size: Option<(u8, u8)>,
+    pub target_value: Option<u32>,
+    // replay file to watch instead of playing
+    pub replay: Option<PathBuf>,
+    // moves per second
+    pub autoplay_rate: Option<f32>,
+    // one of STRATEGY_NAMES
+    pub strategy: Option<String>,
+}
+
+impl LaunchOptions {
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = LaunchOptions::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = args.next().ok_or("--seed expects a number")?;
+                    options.seed = Some(parse_number(&arg, &value)?);
+                }
+                "--size" => {
+                    let value = args.next().ok_or("--size expects a size like 4 or 5x3")?;
+                    options.board_size = Some(parse_size(&value)?);
+                }
+                "--target" => {
+                    let value = args.next().ok_or("--target expects a tile value")?;
+                    options.target_value = Some(parse_target(&value)?);
+                }
+                "--replay" => {
+                    let value = args.next().ok_or("--replay expects a replay file")?;
+                    options.replay = Some(PathBuf::from(value));
+                }
+                "--autoplay-rate" => {
+                    let value = args
+                        .next()
+                        .ok_or("--autoplay-rate expects moves per second")?;
+                    let rate = value
+                        .parse()
+                        .ok()
+                        .filter(|rate: &f32| rate.is_finite() && *rate > 0.0)
+                        .ok_or_else(|| format!("invalid autoplay rate: {value}"))?;
+                    options.autoplay_rate = Some(rate);
+                }
+                "--strategy" => {
+                    let names = STRATEGY_NAMES.join(", ");
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("--strategy expects one of {names}"))?;
+                    if !STRATEGY_NAMES.contains(&value.as_str()) {
+                        return Err(format!(
+                            "unknown strategy: {value}, expected one of {names}"
+                        ));
+                    }
+                    options.strategy = Some(value);
+                }
+                _ => return Err(format!("unknown argument: {arg}")),
+            }
+        }
+        if let (Some(name), Some((width, height))) = (&options.strategy, options.board_size) {
+            strategy::check_board_size(name, width, height)?;
+        }
+        Ok(options)
+    }
+
+    // settings for the first game, anything not given on the command line keeps its default
+    pub fn game_settings(&self) -> GameSettings {
+        let mut settings = GameSettings::default();
+        if let Some((width, height)) = self.board_size {
+            settings.board_width = width;
+            settings.board_height = height;
+        }
+        if let Some(target_value) = self.target_value {
+            settings.target_value = target_value;
+        }
+        settings
+    }
+
+    // fails when the strategy can't be built, e.g. the n-tuple player without weights
+    #[cfg(feature = "gui")]
+    pub fn autoplay(&self, spawn_rules: &SpawnRules) -> Result<Autoplay, String> {
+        let name = self.strategy.as_deref().unwrap_or(STRATEGY_NAMES[0]);
+        let strategy = strategy::by_name(name, spawn_rules)?;
+        Ok(Autoplay::new(
+            self.autoplay_rate.unwrap_or(DEFAULT_MOVES_PER_SECOND),
+            strategy,
+        ))
+    }
+}
+
+// "5x3" is 5 wide and 3 high, a single number is a square board
+pub fn parse_board_size(value: &str) -> Option<(u8, u8)> {
+    let (width, height) = value.split_once('x').unwrap_or((value, value));
+    let side = |side: &str| {
+        side.parse()
+            .ok()
+            .filter(|side| (MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(side))
+    };
+    Some((side(width)?, side(height)?))
+}
+
+// The parsers below are shared with the simulate, train and 2048-tui binaries, so the same
+// flag means the same thing everywhere. Each one explains what was wrong with the value.
+
+// the value of `--size`, with the sides in range
+pub fn parse_size(value: &str) -> Result<(u8, u8), String> {
+    parse_board_size(value).ok_or_else(|| {
+        format!(
+            "invalid board size: {value}, each side must be {MIN_BOARD_SIZE} to {MAX_BOARD_SIZE}"
+        )
+    })
+}
+
+// the value of `--target`, a power of two above the 2s every game starts with
+pub fn parse_target(value: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|target: &u32| target.is_power_of_two() && *target > 2)
+        .ok_or_else(|| format!("invalid target tile: {value}, expected a power of two like 2048"))
+}
+
+// the value of a flag that takes a number, e.g. `--seed 42`
+pub fn parse_number<T: FromStr>(arg: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{arg} expects a number, got {value}"))
+}
@@ -1,9 +1,47 @@
+#[cfg(feature = "gui")]
+pub mod achievement_unlocked_event;
+#[cfg(feature = "gui")]
+pub mod achievements;
+#[cfg(feature = "gui")]
+pub mod autoplay;
+#[cfg(feature = "gui")]
 pub mod board;
 pub mod board_shift;
+#[cfg(feature = "gui")]
+pub mod board_shift_event;
+#[cfg(feature = "gui")]
 pub mod font_spec;
+#[cfg(feature = "gui")]
 pub mod game;
+#[cfg(feature = "gui")]
+pub mod game_event;
+#[cfg(feature = "gui")]
+pub mod game_rng;
+pub mod game_settings;
+#[cfg(feature = "gui")]
+pub mod history_event;
+#[cfg(feature = "gui")]
+pub mod invalid_move_event;
+pub mod launch_options;
+#[cfg(feature = "gui")]
+pub mod move_history;
+#[cfg(feature = "gui")]
 pub mod new_tile_event;
+#[cfg(feature = "gui")]
 pub mod points;
 pub mod position;
+pub mod records;
+pub mod replay;
+#[cfg(feature = "gui")]
+pub mod replay_control;
+#[cfg(feature = "gui")]
+pub mod replay_player;
+#[cfg(feature = "gui")]
 pub mod run_state;
+#[cfg(feature = "gui")]
+pub mod saved_game;
+#[cfg(feature = "gui")]
+pub mod screen;
+pub mod spawn_rules;
+#[cfg(feature = "gui")]
 pub mod tile_text;
@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use rand_pcg::Pcg64;
+
+use crate::engine::{grid::Grid, move_outcome::TileMove};
+
+use super::board_shift::BoardShift;
+
+// The game as it was right before a move, plus what the move did to each tile so
+// undo can animate the tiles back to where they came from.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub board_shift: BoardShift,
+    pub grid: Grid,
+    pub score: u32,
+    pub won: bool,
+    // state before the tile spawned after this move was drawn
+    pub rng: Pcg64,
+    pub moves: Vec<TileMove>,
+}
+
+impl HistoryEntry {
+    fn same_move(&self, other: &HistoryEntry) -> bool {
+        self.board_shift == other.board_shift
+            && self.grid == other.grid
+            && self.score == other.score
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct MoveHistory {
+    undo: Vec<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+}
+
+impl MoveHistory {
+    pub fn record(&mut self, entry: HistoryEntry) {
+        // Moves are deterministic once the rng is restored, so playing the move at the top
+        // of the redo stack is a redo and the rest of the stack stays valid. Any other move
+        // starts a new branch.
+        match self.redo.last() {
+            Some(next) if next.same_move(&entry) => {
+                self.redo.pop();
+            }
+            _ => self.redo.clear(),
+        }
+        self.undo.push(entry);
+    }
+
+    // the entry to restore, it moves over to the redo stack
+    pub fn undo(&mut self) -> Option<&HistoryEntry> {
+        let entry = self.undo.pop()?;
+        self.redo.push(entry);
+        self.redo.last()
+    }
+
+    // the move to play again, record() takes it off the redo stack once it is applied
+    pub fn redo(&self) -> Option<BoardShift> {
+        self.redo.last().map(|entry| entry.board_shift)
+    }
+
+    // every move that led to the current board, oldest first
+    pub fn moves(&self) -> impl Iterator<Item = BoardShift> + '_ {
+        self.undo.iter().map(|entry| entry.board_shift)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
@@ -1 +1,7 @@
-pub struct NewTileEvent;
+use crate::engine::grid::Grid;
+
+// sent after a move changed the board. The merged away tiles are only despawned at the end
+// of the frame, so the grid after the move says which cells the new tiles can go in
+pub struct NewTileEvent {
+    pub grid: Grid,
+}
@@ -1,6 +1,9 @@
+#[cfg(feature = "gui")]
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Component, Debug, PartialEq, Copy, Clone, Eq, Hash)]
+#[cfg_attr(feature = "gui", derive(Component))]
+#[derive(Debug, PartialEq, Copy, Clone, Eq, Hash, Serialize, Deserialize)]
 pub struct Position {
     pub x: u8,
     pub y: u8,
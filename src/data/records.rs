@@ -0,0 +1,264 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    io,
+    path::PathBuf,
+};
+
+#[cfg(feature = "gui")]
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+use super::{board_shift::BoardShift, spawn_rules::SpawnRules};
+
+// Scores are only comparable between games played with the same board and spawns
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleSet {
+    pub width: u8,
+    pub height: u8,
+    pub spawn_values: Vec<(u32, u32)>,
+    pub starting_tiles: usize,
+    pub tiles_per_move: usize,
+}
+
+impl RuleSet {
+    pub fn new(width: u8, height: u8, spawn_rules: &SpawnRules) -> Self {
+        RuleSet {
+            width,
+            height,
+            spawn_values: spawn_rules.values.clone(),
+            starting_tiles: spawn_rules.starting_tiles,
+            tiles_per_move: spawn_rules.tiles_per_move,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BestScore {
+    pub rules: RuleSet,
+    pub score: u32,
+}
+
+// how a single game ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameSummary {
+    pub score: u32,
+    pub highest_tile: u32,
+    pub moves: u32,
+    pub won: bool,
+    // every move that changed the board, in order
+    pub shifts: Vec<BoardShift>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub games_played: u32,
+    pub games_won: u32,
+    pub total_score: u64,
+    pub total_moves: u64,
+    pub highest_tile: u32,
+    // final score of every game, records written before this was added only have the totals
+    pub scores: Vec<u32>,
+    // how many games ended with each highest tile
+    pub highest_tiles: BTreeMap<u32, u32>,
+    // how often each direction was played, over all games
+    pub shifts: HashMap<BoardShift, u64>,
+}
+
+impl LifetimeStats {
+    // fraction of games won, between 0 and 1
+    pub fn win_rate(&self) -> f32 {
+        match self.games_played {
+            0 => 0.0,
+            played => self.games_won as f32 / played as f32,
+        }
+    }
+
+    pub fn average_score(&self) -> f32 {
+        match self.games_played {
+            0 => 0.0,
+            played => self.total_score as f32 / played as f32,
+        }
+    }
+
+    pub fn median_score(&self) -> f32 {
+        let mut scores = self.scores.clone();
+        scores.sort_unstable();
+        let middle = scores.len() / 2;
+        match scores.len() {
+            0 => 0.0,
+            len if len % 2 == 0 => (scores[middle - 1] as f32 + scores[middle] as f32) / 2.0,
+            _ => scores[middle] as f32,
+        }
+    }
+
+    pub fn average_moves(&self) -> f32 {
+        match self.games_played {
+            0 => 0.0,
+            played => self.total_moves as f32 / played as f32,
+        }
+    }
+
+    pub fn shift_count(&self, board_shift: BoardShift) -> u64 {
+        self.shifts.get(&board_shift).copied().unwrap_or(0)
+    }
+}
+
+// how many games the leaderboard keeps for each board size
+pub const LEADERBOARD_SIZE: usize = 10;
+
+// a finished game on the leaderboard
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub score: u32,
+    pub highest_tile: u32,
+    pub moves: u32,
+    pub width: u8,
+    pub height: u8,
+    pub seed: u64,
+    // seconds since the Unix epoch, together with the seed these identify the game
+    pub started_at: u64,
+    pub finished_at: u64,
+    // seconds spent playing, entries written before this was tracked only have the above
+    #[serde(default)]
+    pub play_time: Option<u64>,
+}
+
+impl LeaderboardEntry {
+    // time spent playing, or for older entries the wall clock time between starting and
+    // finishing, including time spent away from a saved game
+    pub fn duration(&self) -> u64 {
+        self.play_time
+            .unwrap_or_else(|| self.finished_at.saturating_sub(self.started_at))
+    }
+
+    fn same_game(&self, other: &LeaderboardEntry) -> bool {
+        self.started_at == other.started_at && self.seed == other.seed
+    }
+}
+
+// Best scores, the leaderboard and lifetime stats, kept in records.json in the data directory
+#[cfg_attr(feature = "gui", derive(Resource))]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Records {
+    pub best_scores: Vec<BestScore>,
+    // sorted by score, highest first
+    pub leaderboard: Vec<LeaderboardEntry>,
+    pub stats: LifetimeStats,
+}
+
+impl Records {
+    fn path() -> Option<PathBuf> {
+        storage::data_dir().map(|dir| dir.join("records.json"))
+    }
+
+    // a missing or unreadable file starts a fresh set of records
+    pub fn load() -> Self {
+        let Some(path) = Records::path() else {
+            return Records::default();
+        };
+        match storage::load_json(&path) {
+            Ok(records) => records,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Records::default(),
+            Err(err) => {
+                // Bevy's log picks this up in the window, the terminal game has nowhere to show it
+                tracing::warn!("could not read {}: {err}", path.display());
+                Records::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        match Records::path() {
+            Some(path) => storage::save_json(&path, self),
+            None => Ok(()),
+        }
+    }
+
+    pub fn best_score(&self, rules: &RuleSet) -> u32 {
+        self.best_scores
+            .iter()
+            .find(|best| best.rules == *rules)
+            .map_or(0, |best| best.score)
+    }
+
+    pub fn submit_score(&mut self, rules: &RuleSet, score: u32) {
+        match self
+            .best_scores
+            .iter_mut()
+            .find(|best| best.rules == *rules)
+        {
+            Some(best) => best.score = best.score.max(score),
+            None => self.best_scores.push(BestScore {
+                rules: rules.clone(),
+                score,
+            }),
+        }
+    }
+
+    pub fn submit_to_leaderboard(&mut self, entry: LeaderboardEntry) {
+        // a game continued with undo keeps a single entry, with its best ending
+        if let Some(index) = self
+            .leaderboard
+            .iter()
+            .position(|other| other.same_game(&entry))
+        {
+            if self.leaderboard[index].score >= entry.score {
+                return;
+            }
+            self.leaderboard.remove(index);
+        }
+
+        self.leaderboard.push(entry);
+        // stable, so the earlier of two equal scores stays ahead
+        self.leaderboard.sort_by_key(|entry| Reverse(entry.score));
+
+        let mut kept: HashMap<(u8, u8), usize> = HashMap::new();
+        self.leaderboard.retain(|entry| {
+            let count = kept.entry((entry.width, entry.height)).or_default();
+            *count += 1;
+            *count <= LEADERBOARD_SIZE
+        });
+    }
+
+    // the top games, either on every board size or on the given width and height
+    pub fn leaderboard(&self, size: Option<(u8, u8)>) -> Vec<&LeaderboardEntry> {
+        self.leaderboard
+            .iter()
+            .filter(|entry| size.is_none() || size == Some((entry.width, entry.height)))
+            .take(LEADERBOARD_SIZE)
+            .collect()
+    }
+
+    // board sizes that have at least one game on the leaderboard, smallest first
+    pub fn leaderboard_sizes(&self) -> Vec<(u8, u8)> {
+        let mut sizes: Vec<(u8, u8)> = self
+            .leaderboard
+            .iter()
+            .map(|entry| (entry.width, entry.height))
+            .collect();
+        sizes.sort_unstable();
+        sizes.dedup();
+        sizes
+    }
+
+    pub fn add_game(&mut self, summary: &GameSummary) {
+        let stats = &mut self.stats;
+        stats.games_played += 1;
+        if summary.won {
+            stats.games_won += 1;
+        }
+        stats.total_score += u64::from(summary.score);
+        stats.total_moves += u64::from(summary.moves);
+        stats.highest_tile = stats.highest_tile.max(summary.highest_tile);
+        stats.scores.push(summary.score);
+        *stats.highest_tiles.entry(summary.highest_tile).or_default() += 1;
+        for board_shift in summary.shifts.iter() {
+            *stats.shifts.entry(*board_shift).or_default() += 1;
+        }
+    }
+}
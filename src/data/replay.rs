@@ -0,0 +1,106 @@
+//! Replay files, written to the `replays` folder of the data directory when a game ends.
+//!
+//! A replay is a JSON object:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "width": 4,
+//!   "height": 4,
+//!   "spawn_rules": { "values": [[2, 9], [4, 1]], "starting_tiles": 2, "tiles_per_move": 1 },
+//!   "seed": 9313712875210412345,
+//!   "moves": ["Up", "Left", "Left", "Down"],
+//!   "score": 16,
+//!   "started_at": 1760745600
+//! }
+//! ```
+//!
+//! - `version` is bumped whenever the meaning of a field changes.
+//! - `width`/`height` are the board size, `spawn_rules` mirrors SpawnRules. Files with rules
+//!   the game can't play by, see `SpawnRules::check`, are refused.
+//! - `seed` seeds the rng (see `engine::session::seeded_rng`) that places the starting tiles
+//!   and the tile spawned after each move.
+//! - `moves` only lists moves that changed the board, oldest first. Undone moves are left out.
+//! - `score` is the final score, `started_at` the start of the game in seconds since the
+//!   Unix epoch. Neither is needed to play the replay back.
+//!
+//! Feeding `moves` to `engine::session::Session` started from the same size, spawn rules and
+//! seed reproduces the game exactly, see `Replay::simulate`.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{engine::session::Session, storage};
+
+use super::{
+    board_shift::BoardShift,
+    game_settings::{MAX_BOARD_SIZE, MIN_BOARD_SIZE},
+    spawn_rules::SpawnRules,
+};
+
+pub const REPLAY_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub width: u8,
+    pub height: u8,
+    pub spawn_rules: SpawnRules,
+    pub seed: u64,
+    pub moves: Vec<BoardShift>,
+    pub score: u32,
+    pub started_at: u64,
+}
+
+impl Replay {
+    fn dir() -> Option<PathBuf> {
+        storage::data_dir().map(|dir| dir.join("replays"))
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let replay: Replay = storage::load_json(path)?;
+        if replay.version != REPLAY_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported replay version {}", replay.version),
+            ));
+        }
+        let sizes = MIN_BOARD_SIZE..=MAX_BOARD_SIZE;
+        if !sizes.contains(&replay.width) || !sizes.contains(&replay.height) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported board size {}x{}", replay.width, replay.height),
+            ));
+        }
+        let cells = usize::from(replay.width) * usize::from(replay.height);
+        replay
+            .spawn_rules
+            .check(cells)
+            .map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))?;
+        Ok(replay)
+    }
+
+    // the game as it stands after the last move
+    pub fn simulate(&self) -> Session {
+        let mut session =
+            Session::new(self.width, self.height, self.spawn_rules.clone(), self.seed);
+        for shift in self.moves.iter() {
+            session.play(*shift);
+        }
+        session
+    }
+
+    // one file per game, a game that ends again after an undo overwrites its earlier replay
+    pub fn save(&self) -> io::Result<Option<PathBuf>> {
+        let Some(dir) = Replay::dir() else {
+            return Ok(None);
+        };
+        let path = dir.join(format!("{}-{}.json", self.started_at, self.seed));
+        storage::save_json(&path, self)?;
+        Ok(Some(path))
+    }
+}
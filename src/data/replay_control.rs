@@ -0,0 +1,11 @@
+// sent by the replay buttons and keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayControl {
+    TogglePlay,
+    StepForward,
+    StepBack,
+    Faster,
+    Slower,
+    // plays or undoes moves until this many have been played, clamped to the replay's length
+    Seek(usize),
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::{game_settings::GameSettings, replay::Replay, spawn_rules::SpawnRules};
+
+const MIN_MOVES_PER_SECOND: f32 = 0.5;
+const MAX_MOVES_PER_SECOND: f32 = 32.0;
+
+// The replay being watched in RunState::Replaying. How far along it is comes from
+// MoveHistory, which also lets step back work like undo.
+#[derive(Resource)]
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    pub playing: bool,
+    pub moves_per_second: f32,
+    pub timer: Timer,
+    // move count a seek is heading for, reached one move per frame
+    pub seek_to: Option<usize>,
+    // the board size and spawn rules the replay replaced, put back when it is left
+    pub stashed: Option<(GameSettings, SpawnRules)>,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        let moves_per_second = 4.0;
+        ReplayPlayer {
+            replay,
+            playing: true,
+            moves_per_second,
+            timer: Timer::from_seconds(1.0 / moves_per_second, TimerMode::Repeating),
+            seek_to: None,
+            stashed: None,
+        }
+    }
+
+    // doubles or halves the playback speed
+    pub fn change_speed(&mut self, factor: f32) {
+        self.moves_per_second =
+            (self.moves_per_second * factor).clamp(MIN_MOVES_PER_SECOND, MAX_MOVES_PER_SECOND);
+        self.timer
+            .set_duration(Duration::from_secs_f32(1.0 / self.moves_per_second));
+    }
+}
@@ -4,5 +4,14 @@ use bevy::prelude::*;
 pub enum RunState {
     #[default] // default attribute macro to specify default state
     Playing,
+    // the target tile was reached, the player picks between keep going and a new game
+    Won,
     GameOver,
+    // a recorded game is played back through the same systems as a live one
+    Replaying,
+}
+
+// run condition for the systems that move and draw tiles
+pub fn board_in_play(run_state: Res<State<RunState>>) -> bool {
+    matches!(run_state.0, RunState::Playing | RunState::Replaying)
 }
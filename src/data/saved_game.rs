@@ -0,0 +1,89 @@
+use std::{collections::HashSet, fs, io, path::PathBuf};
+
+use bevy::prelude::*;
+use rand_pcg::Pcg64;
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+use super::{
+    board_shift::BoardShift,
+    game_settings::{MAX_BOARD_SIZE, MIN_BOARD_SIZE},
+    position::Position,
+};
+
+// An unfinished game, kept in save.json in the data directory. While this resource exists
+// the next new game restores it instead of spawning fresh tiles.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub width: u8,
+    pub height: u8,
+    pub tiles: Vec<(Position, u32)>,
+    pub score: u32,
+    pub moves: u32,
+    pub won: bool,
+    #[serde(default)]
+    pub started_at: u64,
+    #[serde(default)]
+    pub autoplayed: bool,
+    // Game::play_time in seconds
+    #[serde(default)]
+    pub play_time: u64,
+    pub seed: u64,
+    // the rng as it was when the game was saved, so the next spawns match the unsaved game
+    pub rng: Pcg64,
+    // every move since the start, so undo and replays keep working after a resume
+    #[serde(default)]
+    pub shifts: Vec<BoardShift>,
+}
+
+impl SavedGame {
+    fn path() -> Option<PathBuf> {
+        storage::data_dir().map(|dir| dir.join("save.json"))
+    }
+
+    pub fn load() -> Option<Self> {
+        let path = SavedGame::path()?;
+        match storage::load_json::<SavedGame>(&path) {
+            Ok(saved) if saved.is_valid() => Some(saved),
+            Ok(_) => {
+                warn!(
+                    "ignoring {}, the board doesn't fit together",
+                    path.display()
+                );
+                None
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => {
+                warn!("could not read {}: {err}", path.display());
+                None
+            }
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        match SavedGame::path() {
+            Some(path) => storage::save_json(&path, self),
+            None => Ok(()),
+        }
+    }
+
+    // a finished game has nothing to resume
+    pub fn delete() -> io::Result<()> {
+        match SavedGame::path().map(fs::remove_file) {
+            Some(Err(err)) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    // hand edited or outdated files shouldn't be able to crash the game
+    fn is_valid(&self) -> bool {
+        let sizes = MIN_BOARD_SIZE..=MAX_BOARD_SIZE;
+        let mut cells = HashSet::new();
+        sizes.contains(&self.width)
+            && sizes.contains(&self.height)
+            && self.tiles.iter().all(|(pos, value)| {
+                pos.x < self.width && pos.y < self.height && *value > 0 && cells.insert(*pos)
+            })
+    }
+}
@@ -0,0 +1,12 @@
+use bevy::prelude::*;
+
+// Which screen is shown on top of the board. Kept apart from RunState so looking at the
+// scores doesn't end or restart the game underneath.
+#[derive(Default, Debug, Clone, Eq, PartialEq, Hash, States)]
+pub enum Screen {
+    #[default]
+    Board,
+    Leaderboard,
+    Stats,
+    Achievements,
+}
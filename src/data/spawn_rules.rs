@@ -0,0 +1,65 @@
+#[cfg(feature = "gui")]
+use bevy::prelude::*;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// What a freshly spawned tile can be and how many show up at once
+#[cfg_attr(feature = "gui", derive(Resource))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpawnRules {
+    // tile values paired with their relative weights
+    pub values: Vec<(u32, u32)>,
+    pub starting_tiles: usize,
+    pub tiles_per_move: usize,
+}
+
+impl Default for SpawnRules {
+    // standard 2048: a 2 nine times out of ten, otherwise a 4
+    fn default() -> Self {
+        SpawnRules {
+            values: vec![(2, 9), (4, 1)],
+            starting_tiles: 2,
+            tiles_per_move: 1,
+        }
+    }
+}
+
+impl SpawnRules {
+    // rules choose_value and the spawn systems can play by on a board with `cells` cells
+    pub fn check(&self, cells: usize) -> Result<(), String> {
+        if self.values.iter().any(|(value, _)| *value == 0) {
+            return Err("spawn rules can't spawn a 0 tile".to_string());
+        }
+        let total_weight = self
+            .values
+            .iter()
+            .try_fold(0u32, |total, (_, weight)| total.checked_add(*weight));
+        match total_weight {
+            Some(0) => {
+                return Err("spawn rules need at least one value with a non-zero weight".to_string())
+            }
+            None => return Err("spawn rule weights add up to more than a u32".to_string()),
+            Some(_) => {}
+        }
+        if !(1..=cells).contains(&self.starting_tiles) {
+            return Err(format!(
+                "{} starting tiles don't fit a board of {cells} cells",
+                self.starting_tiles
+            ));
+        }
+        if !(1..=cells).contains(&self.tiles_per_move) {
+            return Err(format!(
+                "{} tiles per move don't fit a board of {cells} cells",
+                self.tiles_per_move
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn choose_value(&self, rng: &mut impl Rng) -> u32 {
+        self.values
+            .choose_weighted(rng, |(_, weight)| *weight)
+            .map(|(value, _)| *value)
+            .expect("spawn rules need at least one value with a non-zero weight")
+    }
+}
@@ -0,0 +1,394 @@
+use std::sync::OnceLock;
+
+use itertools::Itertools;
+use rand::prelude::*;
+
+use crate::data::{board_shift::BoardShift, position::Position, spawn_rules::SpawnRules};
+
+use super::grid::Grid;
+
+/// The board size a bitboard covers, both ways.
+pub const SIZE: u8 = 4;
+
+// 2^15 = 32768 is the largest tile that fits in a cell
+const MAX_EXPONENT: u8 = 15;
+
+/// A 4x4 grid packed into a `u64` for bots and simulations. Each cell takes four bits holding
+/// the exponent of its tile (1 for a 2, 2 for a 4, ...) or 0 when it's empty. Cell `(x, y)`
+/// sits at bits `16 * y + 4 * x`, so every row of the board is one `u16`.
+///
+/// Moves follow `Grid::apply` exactly, with one exception: two 32768 tiles don't merge,
+/// because the result wouldn't fit in four bits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    /// None unless the grid is 4x4 and every tile is a power of two from 2 to 32768.
+    pub fn from_grid(grid: &Grid) -> Option<Self> {
+        if grid.width() != SIZE || grid.height() != SIZE {
+            return None;
+        }
+        let mut board = Bitboard::default();
+        for pos in grid.positions() {
+            if let Some(value) = grid.get(pos) {
+                board.set_exponent(pos, exponent(value)?);
+            }
+        }
+        Some(board)
+    }
+
+    pub fn to_grid(self) -> Grid {
+        Grid::from_tiles(
+            SIZE,
+            SIZE,
+            self.positions()
+                .filter_map(|pos| self.get(pos).map(|value| (pos, value))),
+        )
+    }
+
+    fn shift(pos: Position) -> u32 {
+        16 * u32::from(pos.y) + 4 * u32::from(pos.x)
+    }
+
+    fn exponent(self, pos: Position) -> u8 {
+        ((self.0 >> Bitboard::shift(pos)) & 0xf) as u8
+    }
+
+    fn set_exponent(&mut self, pos: Position, exponent: u8) {
+        let shift = Bitboard::shift(pos);
+        self.0 = (self.0 & !(0xf << shift)) | (u64::from(exponent) << shift);
+    }
+
+    pub fn get(self, pos: Position) -> Option<u32> {
+        match self.exponent(pos) {
+            0 => None,
+            exponent => Some(1 << exponent),
+        }
+    }
+
+    /// True for the tile values a bitboard can hold: powers of two from 2 to 32768.
+    pub fn holds(value: u32) -> bool {
+        exponent(value).is_some()
+    }
+
+    /// Panics if `value` isn't a power of two from 2 to 32768.
+    pub fn set(&mut self, pos: Position, value: u32) {
+        let exponent = exponent(value).expect("bitboard tiles are powers of two up to 32768");
+        self.set_exponent(pos, exponent);
+    }
+
+    /// Every cell, in the same order as `Grid::positions`.
+    pub fn positions(self) -> impl Iterator<Item = Position> {
+        (0..SIZE)
+            .cartesian_product(0..SIZE)
+            .map(|(x, y)| Position { x, y })
+    }
+
+    pub fn empty_positions(self) -> impl Iterator<Item = Position> {
+        self.positions().filter(move |&pos| self.exponent(pos) == 0)
+    }
+
+    pub fn count_empty(self) -> u32 {
+        // fold every nibble onto its lowest bit, an empty cell ends up as a zero bit
+        let mut folded = self.0;
+        folded |= folded >> 2;
+        folded |= folded >> 1;
+        16 - (folded & 0x1111_1111_1111_1111).count_ones()
+    }
+
+    pub fn highest_tile(self) -> Option<u32> {
+        self.positions().filter_map(|pos| self.get(pos)).max()
+    }
+
+    /// Place a tile from `rules` on a random empty cell, or None if the board is full.
+    /// Draws from `rng` exactly like `Grid::spawn`, so the same seed spawns the same tiles.
+    pub fn spawn(&mut self, rules: &SpawnRules, rng: &mut impl Rng) -> Option<(Position, u32)> {
+        let pos = self.empty_positions().choose(rng)?;
+        let value = rules.choose_value(rng);
+        self.set(pos, value);
+        Some((pos, value))
+    }
+
+    /// True while some shift would change the board.
+    pub fn has_moves(self) -> bool {
+        BoardShift::ALL
+            .into_iter()
+            .any(|shift| self.apply(shift).0 != self)
+    }
+
+    /// The board after sliding every tile towards `shift`, and the points scored by merges.
+    pub fn apply(self, shift: BoardShift) -> (Bitboard, u32) {
+        let tables = row_tables();
+        // up and down work on the columns, which transposing turns into rows
+        let (board, table, scores) = match shift {
+            BoardShift::Left => (self.0, &tables.left, &tables.left_score),
+            BoardShift::Right => (self.0, &tables.right, &tables.right_score),
+            // cell 0 of a column is its bottom cell
+            BoardShift::Down => (transpose(self.0), &tables.left, &tables.left_score),
+            BoardShift::Up => (transpose(self.0), &tables.right, &tables.right_score),
+        };
+
+        let mut moved = 0;
+        let mut score = 0;
+        for row in 0..4 {
+            let line = ((board >> (16 * row)) & 0xffff) as usize;
+            moved |= u64::from(table[line]) << (16 * row);
+            score += scores[line];
+        }
+
+        match shift {
+            BoardShift::Left | BoardShift::Right => (Bitboard(moved), score),
+            BoardShift::Up | BoardShift::Down => (Bitboard(transpose(moved)), score),
+        }
+    }
+}
+
+fn exponent(value: u32) -> Option<u8> {
+    let exponent = value.trailing_zeros() as u8;
+    (value.is_power_of_two() && (1..=MAX_EXPONENT).contains(&exponent)).then_some(exponent)
+}
+
+// swap cell (x, y) with cell (y, x)
+fn transpose(board: u64) -> u64 {
+    let a1 = board & 0xf0f0_0f0f_f0f0_0f0f;
+    let a2 = board & 0x0000_f0f0_0000_f0f0;
+    let a3 = board & 0x0f0f_0000_0f0f_0000;
+    let a = a1 | (a2 << 12) | (a3 >> 12);
+    let b1 = a & 0xff00_ff00_00ff_00ff;
+    let b2 = a & 0x00ff_00ff_0000_0000;
+    let b3 = a & 0x0000_0000_ff00_ff00;
+    b1 | (b2 >> 24) | (b3 << 24)
+}
+
+// every possible row moved towards cell 0 (left) and towards cell 3 (right)
+struct RowTables {
+    left: Vec<u16>,
+    left_score: Vec<u32>,
+    right: Vec<u16>,
+    right_score: Vec<u32>,
+}
+
+fn row_tables() -> &'static RowTables {
+    static TABLES: OnceLock<RowTables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let rows = 1 << 16;
+        let mut tables = RowTables {
+            left: vec![0; rows],
+            left_score: vec![0; rows],
+            right: vec![0; rows],
+            right_score: vec![0; rows],
+        };
+        for row in 0..=u16::MAX {
+            let (moved, score) = slide_row(row);
+            tables.left[usize::from(row)] = moved;
+            tables.left_score[usize::from(row)] = score;
+            // moving right is moving the mirrored row left
+            let mirrored = reverse_row(row);
+            tables.right[usize::from(mirrored)] = reverse_row(moved);
+            tables.right_score[usize::from(mirrored)] = score;
+        }
+        tables
+    })
+}
+
+fn reverse_row(row: u16) -> u16 {
+    (row >> 12) | ((row >> 4) & 0x00f0) | ((row << 4) & 0x0f00) | (row << 12)
+}
+
+// the same walk as Grid::apply, on the exponents of one row
+fn slide_row(row: u16) -> (u16, u32) {
+    let mut tiles = (0..4)
+        .map(|cell| (row >> (4 * cell)) & 0xf)
+        .filter(|&exponent| exponent != 0)
+        .peekable();
+
+    let mut moved = 0;
+    let mut score = 0;
+    let mut cell = 0;
+    while let Some(exponent) = tiles.next() {
+        if exponent < u16::from(MAX_EXPONENT) && tiles.next_if_eq(&exponent).is_some() {
+            score += 1 << (exponent + 1);
+            moved |= (exponent + 1) << (4 * cell);
+        } else {
+            moved |= exponent << (4 * cell);
+        }
+        cell += 1;
+    }
+    (moved, score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::session::seeded_rng;
+
+    // a grid with random tiles up to 2048 and about a third of the cells empty
+    fn random_grid(rng: &mut impl Rng) -> Grid {
+        let mut grid = Grid::new(SIZE, SIZE);
+        for pos in grid.positions().collect::<Vec<_>>() {
+            if rng.gen_ratio(2, 3) {
+                grid.set(pos, 1 << rng.gen_range(1..=11));
+            }
+        }
+        grid
+    }
+
+    fn assert_same_move(grid: &Grid, shift: BoardShift) {
+        let board = Bitboard::from_grid(grid).unwrap();
+        let (moved, score) = board.apply(shift);
+
+        let mut expected = grid.clone();
+        let outcome = expected.apply(shift);
+        assert_eq!(moved.to_grid(), expected, "{shift:?} on {grid:?}");
+        assert_eq!(score, outcome.score, "{shift:?} on {grid:?}");
+        assert_eq!(moved != board, outcome.changed(), "{shift:?} on {grid:?}");
+    }
+
+    #[test]
+    fn converts_to_and_from_grid() {
+        let mut rng = seeded_rng(1);
+        for _ in 0..1000 {
+            let grid = random_grid(&mut rng);
+            let board = Bitboard::from_grid(&grid).unwrap();
+            assert_eq!(board.to_grid(), grid);
+            assert_eq!(board.highest_tile(), grid.highest_tile());
+            assert_eq!(
+                board.empty_positions().collect::<Vec<_>>(),
+                grid.empty_positions().collect::<Vec<_>>()
+            );
+            assert_eq!(board.count_empty() as usize, grid.empty_positions().count());
+            assert_eq!(board.has_moves(), grid.has_moves());
+        }
+    }
+
+    #[test]
+    fn rejects_grids_it_cannot_hold() {
+        assert_eq!(Bitboard::from_grid(&Grid::new(5, 4)), None);
+        assert_eq!(Bitboard::from_grid(&Grid::new(3, 3)), None);
+
+        let origin = Position { x: 0, y: 0 };
+        for value in [1, 3, 6, 65536] {
+            let grid = Grid::from_tiles(SIZE, SIZE, [(origin, value)]);
+            assert_eq!(Bitboard::from_grid(&grid), None, "{value}");
+        }
+        let grid = Grid::from_tiles(SIZE, SIZE, [(origin, 32768)]);
+        assert_eq!(Bitboard::from_grid(&grid).unwrap().get(origin), Some(32768));
+    }
+
+    #[test]
+    fn transpose_swaps_rows_and_columns() {
+        let mut rng = seeded_rng(2);
+        for _ in 0..1000 {
+            let board = Bitboard(rng.gen());
+            let transposed = Bitboard(transpose(board.0));
+            for pos in board.positions() {
+                let swapped = Position { x: pos.y, y: pos.x };
+                assert_eq!(transposed.exponent(swapped), board.exponent(pos));
+            }
+            assert_eq!(transpose(transposed.0), board.0);
+        }
+    }
+
+    // every row of exponents up to 14 (16384), in every lane and every direction
+    #[test]
+    fn matches_grid_for_every_row() {
+        for row in 0..=u16::MAX {
+            let cells: Vec<u16> = (0..4).map(|cell| (row >> (4 * cell)) & 0xf).collect();
+            if cells.iter().any(|&exponent| exponent > 14) {
+                continue;
+            }
+            let lane = (row % 4) as u8;
+            for shift in BoardShift::ALL {
+                let tiles = cells
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &exponent)| exponent != 0)
+                    .map(|(index, &exponent)| {
+                        let pos = match shift {
+                            BoardShift::Left | BoardShift::Right => Position {
+                                x: index as u8,
+                                y: lane,
+                            },
+                            BoardShift::Up | BoardShift::Down => Position {
+                                x: lane,
+                                y: index as u8,
+                            },
+                        };
+                        (pos, 1 << exponent)
+                    });
+                assert_same_move(&Grid::from_tiles(SIZE, SIZE, tiles), shift);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_grid_on_random_boards() {
+        let mut rng = seeded_rng(3);
+        for _ in 0..10_000 {
+            let grid = random_grid(&mut rng);
+            for shift in BoardShift::ALL {
+                assert_same_move(&grid, shift);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_grid_for_a_merge_of_16384s() {
+        let tiles = [
+            (Position { x: 0, y: 0 }, 16384),
+            (Position { x: 1, y: 0 }, 16384),
+        ];
+        let grid = Grid::from_tiles(SIZE, SIZE, tiles);
+        for shift in BoardShift::ALL {
+            assert_same_move(&grid, shift);
+        }
+    }
+
+    #[test]
+    fn does_not_merge_32768s() {
+        let tiles = [
+            (Position { x: 2, y: 1 }, 32768),
+            (Position { x: 3, y: 1 }, 32768),
+        ];
+        let board = Bitboard::from_grid(&Grid::from_tiles(SIZE, SIZE, tiles)).unwrap();
+        let (moved, score) = board.apply(BoardShift::Left);
+        assert_eq!(score, 0);
+        assert_eq!(moved.get(Position { x: 0, y: 1 }), Some(32768));
+        assert_eq!(moved.get(Position { x: 1, y: 1 }), Some(32768));
+    }
+
+    // whole games from the same seed end up on the same board, spawns included
+    #[test]
+    fn plays_the_same_games_as_grid() {
+        let rules = SpawnRules::default();
+        for seed in 0..20 {
+            let mut grid_rng = seeded_rng(seed);
+            let mut board_rng = seeded_rng(seed);
+            let mut grid = Grid::new(SIZE, SIZE);
+            let mut board = Bitboard::default();
+            for _ in 0..rules.starting_tiles {
+                assert_eq!(
+                    board.spawn(&rules, &mut board_rng),
+                    grid.spawn(&rules, &mut grid_rng)
+                );
+            }
+
+            let mut shifts = BoardShift::ALL.iter().cycle();
+            while grid.has_moves() {
+                let shift = *shifts.next().unwrap();
+                let outcome = grid.apply(shift);
+                let (moved, score) = board.apply(shift);
+                assert_eq!(score, outcome.score);
+                board = moved;
+                if outcome.changed() {
+                    assert_eq!(
+                        board.spawn(&rules, &mut board_rng),
+                        grid.spawn(&rules, &mut grid_rng)
+                    );
+                }
+                assert_eq!(board.to_grid(), grid);
+            }
+            assert!(!board.has_moves());
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use itertools::Itertools;
+use rand::prelude::*;
+
+use crate::data::{board_shift::BoardShift, position::Position, spawn_rules::SpawnRules};
+
+use super::move_outcome::{MoveOutcome, TileMove};
+
+/// The 2048 rules on a plain grid of tile values, without any Bevy world.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid {
+    width: u8,
+    height: u8,
+    // row-major, None marks an empty cell
+    cells: Vec<Option<u32>>,
+}
+
+impl Grid {
+    pub fn new(width: u8, height: u8) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![None; usize::from(width) * usize::from(height)],
+        }
+    }
+
+    pub fn from_tiles(
+        width: u8,
+        height: u8,
+        tiles: impl IntoIterator<Item = (Position, u32)>,
+    ) -> Self {
+        let mut grid = Grid::new(width, height);
+        for (pos, value) in tiles {
+            grid.set(pos, value);
+        }
+        grid
+    }
+
+    pub fn width(&self) -> u8 {
+        self.width
+    }
+
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    fn index(&self, pos: Position) -> usize {
+        usize::from(pos.y) * usize::from(self.width) + usize::from(pos.x)
+    }
+
+    pub fn get(&self, pos: Position) -> Option<u32> {
+        self.cells[self.index(pos)]
+    }
+
+    pub fn set(&mut self, pos: Position, value: u32) {
+        let index = self.index(pos);
+        self.cells[index] = Some(value);
+    }
+
+    pub fn clear(&mut self, pos: Position) {
+        let index = self.index(pos);
+        self.cells[index] = None;
+    }
+
+    /// Every cell on the grid, in the same order as `(0..width).cartesian_product(0..height)`.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        (0..self.width)
+            .cartesian_product(0..self.height)
+            .map(|(x, y)| Position { x, y })
+    }
+
+    pub fn highest_tile(&self) -> Option<u32> {
+        self.cells.iter().flatten().copied().max()
+    }
+
+    pub fn empty_positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.positions().filter(|&pos| self.get(pos).is_none())
+    }
+
+    /// Place a tile from `rules` on a random empty cell, or None if the grid is full.
+    pub fn spawn(&mut self, rules: &SpawnRules, rng: &mut impl Rng) -> Option<(Position, u32)> {
+        let pos = self.empty_positions().choose(rng)?;
+        let value = rules.choose_value(rng);
+        self.set(pos, value);
+        Some((pos, value))
+    }
+
+    /// True while there is an empty cell or two equal neighbors that could merge.
+    pub fn has_moves(&self) -> bool {
+        self.positions().any(|pos| match self.get(pos) {
+            None => true,
+            Some(value) => {
+                // checking the right and upper neighbor covers every adjacent pair once
+                let right = Position {
+                    x: pos.x + 1,
+                    y: pos.y,
+                };
+                let up = Position {
+                    x: pos.x,
+                    y: pos.y + 1,
+                };
+                (right.x < self.width && self.get(right) == Some(value))
+                    || (up.y < self.height && self.get(up) == Some(value))
+            }
+        })
+    }
+
+    /// Slide every tile towards `shift`, merging equal neighbors once per move.
+    pub fn apply(&mut self, shift: BoardShift) -> MoveOutcome {
+        let mut outcome = MoveOutcome::default();
+
+        let (rows, row_length) = shift.rows(self.width, self.height);
+        for row in 0..rows {
+            // tiles in this row, starting from the edge they are being pushed against
+            let line: Vec<(Position, u32)> = (0..row_length)
+                .map(|index| shift.cell_position(self.width, self.height, row, index))
+                .filter_map(|pos| self.get(pos).map(|value| (pos, value)))
+                .collect();
+            for (pos, _) in line.iter() {
+                self.clear(*pos);
+            }
+
+            let mut column: u8 = 0;
+            let mut it = line.into_iter().peekable();
+            while let Some((from, value)) = it.next() {
+                let to = shift.cell_position(self.width, self.height, row, column);
+                outcome.moves.push(TileMove {
+                    from,
+                    to,
+                    absorbed: false,
+                });
+
+                // a tile merges with the next tile in its row if the values match,
+                // and the merged tile can't merge again during this move
+                match it.next_if(|(_, next_value)| *next_value == value) {
+                    Some((next, next_value)) => {
+                        let merged = value + next_value;
+                        outcome.moves.push(TileMove {
+                            from: next,
+                            to,
+                            absorbed: true,
+                        });
+                        outcome.merged.push(merged);
+                        outcome.score += merged;
+                        self.set(to, merged);
+                    }
+                    None => self.set(to, value),
+                }
+                column += 1;
+            }
+        }
+
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a 4x4 grid with `values` along the first row of `shift`, listed from the edge the
+    // tiles are pushed against
+    fn line(shift: BoardShift, values: [Option<u32>; 4]) -> Grid {
+        let mut grid = Grid::new(4, 4);
+        for (index, value) in values.into_iter().enumerate() {
+            if let Some(value) = value {
+                grid.set(shift.cell_position(4, 4, 0, index as u8), value);
+            }
+        }
+        grid
+    }
+
+    fn read_line(grid: &Grid, shift: BoardShift) -> [Option<u32>; 4] {
+        [0, 1, 2, 3].map(|index| grid.get(shift.cell_position(4, 4, 0, index)))
+    }
+
+    #[test]
+    fn merges_a_full_row_in_pairs() {
+        for shift in BoardShift::ALL {
+            let mut grid = line(shift, [Some(2); 4]);
+            let outcome = grid.apply(shift);
+            assert_eq!(read_line(&grid, shift), [Some(4), Some(4), None, None]);
+            assert_eq!(outcome.merged, vec![4, 4]);
+            assert_eq!(outcome.score, 8);
+            assert!(outcome.changed());
+        }
+    }
+
+    #[test]
+    fn merges_every_tile_once_per_move() {
+        for shift in BoardShift::ALL {
+            // the new 8 doesn't merge again with the 8 behind it
+            let mut grid = line(shift, [Some(4), Some(4), Some(8), None]);
+            assert_eq!(grid.apply(shift).score, 8);
+            assert_eq!(read_line(&grid, shift), [Some(8), Some(8), None, None]);
+
+            // the pair nearest the edge merges first, across gaps
+            let mut grid = line(shift, [Some(2), None, Some(2), Some(2)]);
+            assert_eq!(grid.apply(shift).merged, vec![4]);
+            assert_eq!(read_line(&grid, shift), [Some(4), Some(2), None, None]);
+        }
+    }
+
+    #[test]
+    fn reports_moves_that_change_nothing() {
+        for shift in BoardShift::ALL {
+            // already against the edge with nothing to merge
+            let mut grid = line(shift, [Some(2), Some(4), None, None]);
+            let outcome = grid.apply(shift);
+            assert!(!outcome.changed());
+            assert_eq!(outcome.score, 0);
+            assert_eq!(read_line(&grid, shift), [Some(2), Some(4), None, None]);
+
+            // sliding without a merge changes the board but scores nothing
+            let mut grid = line(shift, [None, Some(2), None, Some(4)]);
+            let outcome = grid.apply(shift);
+            assert!(outcome.changed());
+            assert_eq!(outcome.score, 0);
+            assert_eq!(read_line(&grid, shift), [Some(2), Some(4), None, None]);
+        }
+    }
+
+    #[test]
+    fn plays_on_rectangular_boards() {
+        let tiles = [
+            (Position { x: 0, y: 0 }, 2),
+            (Position { x: 2, y: 0 }, 2),
+            (Position { x: 4, y: 0 }, 4),
+            (Position { x: 4, y: 2 }, 4),
+        ];
+        let mut grid = Grid::from_tiles(5, 3, tiles);
+
+        let outcome = grid.apply(BoardShift::Right);
+        assert_eq!(outcome.score, 4);
+        assert_eq!(grid.get(Position { x: 4, y: 0 }), Some(4));
+        assert_eq!(grid.get(Position { x: 3, y: 0 }), Some(4));
+        assert_eq!(grid.get(Position { x: 4, y: 2 }), Some(4));
+
+        // the right column is only 3 cells high
+        let outcome = grid.apply(BoardShift::Up);
+        assert_eq!(outcome.merged, vec![8]);
+        assert_eq!(grid.get(Position { x: 4, y: 2 }), Some(8));
+        assert_eq!(grid.get(Position { x: 3, y: 2 }), Some(4));
+        assert_eq!(grid.empty_positions().count(), 13);
+    }
+
+    #[test]
+    fn has_moves_on_a_full_board_only_with_a_merge() {
+        // a checkerboard of 2s and 4s has no equal neighbors
+        let checkerboard = (0..4).flat_map(|y| {
+            (0..4).map(move |x| (Position { x, y }, if (x + y) % 2 == 0 { 2 } else { 4 }))
+        });
+        let mut grid = Grid::from_tiles(4, 4, checkerboard);
+        assert!(grid.empty_positions().next().is_none());
+        assert!(!grid.has_moves());
+
+        // equal neighbors side by side and on top of each other
+        let mut side_by_side = grid.clone();
+        side_by_side.set(Position { x: 1, y: 0 }, 2);
+        assert!(side_by_side.has_moves());
+        let mut stacked = grid.clone();
+        stacked.set(Position { x: 3, y: 3 }, 4);
+        assert!(stacked.has_moves());
+
+        grid.clear(Position { x: 2, y: 1 });
+        assert!(grid.has_moves());
+    }
+}
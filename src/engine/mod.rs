@@ -0,0 +1,4 @@
+pub mod bitboard;
+pub mod grid;
+pub mod move_outcome;
+pub mod session;
@@ -0,0 +1,29 @@
+use crate::data::position::Position;
+
+/// Where a single tile ended up after a shift.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileMove {
+    pub from: Position,
+    pub to: Position,
+    // the tile was merged into the tile that landed on `to` and no longer exists
+    pub absorbed: bool,
+}
+
+/// Everything that happened to the grid during one call to `Grid::apply`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MoveOutcome {
+    // one entry per tile that was on the grid before the shift
+    pub moves: Vec<TileMove>,
+    // the value of every tile created by a merge
+    pub merged: Vec<u32>,
+    pub score: u32,
+}
+
+impl MoveOutcome {
+    /// False when the shift was a no-op, e.g. pushing every tile into a wall.
+    pub fn changed(&self) -> bool {
+        self.moves
+            .iter()
+            .any(|tile_move| tile_move.from != tile_move.to)
+    }
+}
@@ -0,0 +1,103 @@
+use rand::prelude::*;
+use rand_pcg::Pcg64;
+
+use crate::data::{board_shift::BoardShift, spawn_rules::SpawnRules};
+
+use super::{grid::Grid, move_outcome::MoveOutcome};
+
+/// The rng every game draws its tiles from. GameRng seeds the Bevy game the same way,
+/// so a seed means the same tiles everywhere.
+pub fn seeded_rng(seed: u64) -> Pcg64 {
+    Pcg64::seed_from_u64(seed)
+}
+
+/// A whole game without Bevy: the grid plus the score and the rng that spawns new tiles.
+/// Spawns happen in the same order and on the same empty cells as spawn_tiles and
+/// new_tile_handler, which spawns on the grid board_shift left rather than on the tile
+/// entities. new_tile_handler's tests play the window's systems against a Session.
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub grid: Grid,
+    pub score: u32,
+    // moves that changed the board
+    pub moves: u32,
+    pub rng: Pcg64,
+    pub spawn_rules: SpawnRules,
+}
+
+impl Session {
+    pub fn new(width: u8, height: u8, spawn_rules: SpawnRules, seed: u64) -> Self {
+        let mut session = Session {
+            grid: Grid::new(width, height),
+            score: 0,
+            moves: 0,
+            rng: seeded_rng(seed),
+            spawn_rules,
+        };
+        for _ in 0..session.spawn_rules.starting_tiles {
+            session.grid.spawn(&session.spawn_rules, &mut session.rng);
+        }
+        session
+    }
+
+    /// Play a move, new tiles only spawn when it changed the board.
+    pub fn play(&mut self, shift: BoardShift) -> MoveOutcome {
+        let outcome = self.grid.apply(shift);
+        if outcome.changed() {
+            self.score += outcome.score;
+            self.moves += 1;
+            for _ in 0..self.spawn_rules.tiles_per_move {
+                self.grid.spawn(&self.spawn_rules, &mut self.rng);
+            }
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_with_the_starting_tiles() {
+        let session = Session::new(5, 3, SpawnRules::default(), 3);
+        assert_eq!(session.grid.empty_positions().count(), 15 - 2);
+        assert_eq!((session.score, session.moves), (0, 0));
+    }
+
+    #[test]
+    fn spawns_and_scores_only_after_a_change() {
+        let mut session = Session::new(4, 4, SpawnRules::default(), 7);
+        let mut played = 0;
+        for shift in BoardShift::ALL.into_iter().cycle().take(40) {
+            let before = session.clone();
+            let outcome = session.play(shift);
+            let empty_before = before.grid.empty_positions().count();
+            let empty_after = session.grid.empty_positions().count();
+            if outcome.changed() {
+                played += 1;
+                // merges free a cell each, the new tile takes one
+                assert_eq!(empty_after, empty_before + outcome.merged.len() - 1);
+                assert_eq!(session.score, before.score + outcome.score);
+            } else {
+                assert_eq!(session.grid, before.grid);
+                assert_eq!(session.score, before.score);
+            }
+            assert_eq!(session.moves, played);
+        }
+    }
+
+    #[test]
+    fn repeats_a_game_given_the_seed_and_moves() {
+        let moves = [BoardShift::Left, BoardShift::Up, BoardShift::Right];
+        let play = |seed| {
+            let mut session = Session::new(4, 4, SpawnRules::default(), seed);
+            for shift in moves.iter().cycle().take(30) {
+                session.play(*shift);
+            }
+            (session.grid, session.score)
+        };
+        assert_eq!(play(11), play(11));
+        assert_ne!(play(11), play(12));
+    }
+}
@@ -0,0 +1,28 @@
+// The game as a library, so the binaries can share the rules and the bots with it.
+// `main.rs` builds the Bevy app, `bin/` holds the tools that run without a window, which
+// build without Bevy when the default `gui` feature is turned off.
+pub mod ai;
+#[cfg(feature = "gui")]
+pub mod bot;
+#[cfg(feature = "gui")]
+pub mod colors;
+pub mod data;
+pub mod engine;
+pub mod rl;
+pub mod storage;
+#[cfg(feature = "gui")]
+pub mod systems;
+#[cfg(feature = "gui")]
+pub mod ui;
+
+// shorthands the systems and the UI use all over the place
+#[cfg(feature = "gui")]
+use crate::data::board::Board;
+#[cfg(feature = "gui")]
+use crate::data::font_spec::FontSpec;
+#[cfg(feature = "gui")]
+use crate::data::game::Game;
+#[cfg(feature = "gui")]
+use crate::data::game_rng::GameRng;
+#[cfg(feature = "gui")]
+use crate::data::run_state::RunState;
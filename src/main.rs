@@ -1,28 +1,93 @@
-mod colors;
-mod data;
-mod systems;
-mod ui;
-
-use crate::data::board::Board;
-use crate::data::font_spec::FontSpec;
-use crate::data::game::Game;
-use crate::data::new_tile_event::NewTileEvent;
-use crate::data::run_state::RunState;
-use crate::systems::board_shift::board_shift;
-use crate::systems::end_game::end_game;
-use crate::systems::game_reset::game_reset;
-use crate::systems::new_tile_handler::new_tile_handler;
-use crate::systems::render_tile_points::render_tile_points;
-use crate::systems::render_tiles::render_tiles;
-use crate::systems::setup::setup;
-use crate::systems::spawn_board::spawn_board;
-use crate::systems::spawn_tiles::spawn_tiles;
 use bevy::prelude::*;
 use bevy_easings::*;
+use boxes::bot::BotPlugin;
+use boxes::data::achievement_unlocked_event::AchievementUnlockedEvent;
+use boxes::data::achievements::Achievements;
+use boxes::data::board_shift_event::BoardShiftEvent;
+use boxes::data::font_spec::FontSpec;
+use boxes::data::game::Game;
+use boxes::data::game_event::GameEvent;
+use boxes::data::game_rng::GameRng;
+use boxes::data::history_event::HistoryEvent;
+use boxes::data::invalid_move_event::InvalidMoveEvent;
+use boxes::data::launch_options::LaunchOptions;
+use boxes::data::move_history::MoveHistory;
+use boxes::data::new_tile_event::NewTileEvent;
+use boxes::data::records::Records;
+use boxes::data::replay::Replay;
+use boxes::data::replay_control::ReplayControl;
+use boxes::data::replay_player::ReplayPlayer;
+use boxes::data::run_state::{board_in_play, RunState};
+use boxes::data::saved_game::SavedGame;
+use boxes::data::screen::Screen;
+use boxes::data::spawn_rules::SpawnRules;
+use boxes::systems::board_shift::board_shift;
+use boxes::systems::check_win::check_win;
+use boxes::systems::end_game::end_game;
+use boxes::systems::finish_game::{mark_recorded, FinishGamePlugin};
+use boxes::systems::game_reset::{finish_resume, game_reset, starting_new_game};
+use boxes::systems::keyboard_input::{
+    autoplay_input, board_shift_input, history_input, replay_input,
+};
+use boxes::systems::new_tile_handler::new_tile_handler;
+use boxes::systems::nudge_board::nudge_board;
+use boxes::systems::render_tile_points::render_tile_points;
+use boxes::systems::render_tiles::render_tiles;
+use boxes::systems::replay_playback::{leave_replay, replay_playback, setup_replay};
+use boxes::systems::save_game::{restore_saved_game, save_game};
+use boxes::systems::setup::setup;
+use boxes::systems::spawn_board::spawn_board;
+use boxes::systems::spawn_tiles::spawn_tiles;
+use boxes::systems::track_play_time::track_play_time;
+use boxes::systems::undo_redo::undo_redo;
+use boxes::systems::unlock_achievements::unlock_achievements;
+use boxes::ui::GameUiPlugin;
 
 fn main() {
-    App::new()
-        .insert_resource(ClearColor(Color::hex("#1f2638").unwrap()))
+    let launch_options = LaunchOptions::from_args(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+
+    let replay = launch_options.replay.as_ref().map(|path| {
+        Replay::load(path).unwrap_or_else(|err| {
+            eprintln!("could not load replay {}: {err}", path.display());
+            std::process::exit(2);
+        })
+    });
+
+    // the bots look ahead with the same tiles the game spawns
+    let spawn_rules = SpawnRules::default();
+    let autoplay = launch_options.autoplay(&spawn_rules).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        std::process::exit(2);
+    });
+
+    let mut settings = launch_options.game_settings();
+    // asking for a specific seed or board size on the command line starts a new game,
+    // watching a replay leaves the saved game alone for next time
+    let saved_game =
+        if replay.is_none() && launch_options.seed.is_none() && launch_options.board_size.is_none()
+        {
+            SavedGame::load()
+        } else {
+            None
+        };
+    if let Some(saved) = saved_game.as_ref() {
+        settings.board_width = saved.width;
+        settings.board_height = saved.height;
+    }
+
+    let mut app = App::new();
+    if let Some(saved) = saved_game {
+        app.insert_resource(saved);
+    }
+    if let Some(replay) = replay {
+        // add_state keeps a state that is already there, so we start out watching the replay
+        app.insert_resource(ReplayPlayer::new(replay))
+            .insert_resource(State(RunState::Replaying));
+    }
+    app.insert_resource(ClearColor(Color::hex("#1f2638").unwrap()))
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "2048".to_string(),
@@ -31,8 +96,11 @@ fn main() {
             ..default()
         }))
         .add_state::<RunState>()
-        .add_plugin(ui::GameUiPlugin)
+        .add_state::<Screen>()
+        .add_plugin(GameUiPlugin)
         .add_plugin(EasingsPlugin)
+        .add_plugin(BotPlugin)
+        .add_plugin(FinishGamePlugin)
         // We use turbofish syntax because sometimes functions can operate on many different types.
         // It is important to init the font after the default plugins because the default plugins
         // initialize an asset server responsible for loading the font file.
@@ -42,23 +110,114 @@ fn main() {
         // to the world.
         .init_resource::<FontSpec>()
         .init_resource::<Game>()
+        .insert_resource(spawn_rules)
+        .init_resource::<MoveHistory>()
+        .insert_resource(Records::load())
+        .insert_resource(Achievements::load())
+        .insert_resource(GameRng::new(launch_options.seed))
+        .insert_resource(autoplay)
+        .insert_resource(settings)
+        .insert_resource(launch_options)
         .add_event::<NewTileEvent>()
-        // The apply_system_buffers system is used so that spawn_tiles system can query
-        // for a board entity produced from the spawn_board system. Normally these all run in
-        // parallel, which is what you typically want.
-        .add_startup_systems((setup, spawn_board, apply_system_buffers).chain())
+        .add_event::<InvalidMoveEvent>()
+        .add_event::<BoardShiftEvent>()
+        .add_event::<HistoryEvent>()
+        .add_event::<ReplayControl>()
+        .add_event::<GameEvent>()
+        .add_event::<AchievementUnlockedEvent>()
+        .add_startup_system(setup)
+        // undo also works from the game over screen, so these run in every state
+        .add_systems(
+            (
+                history_input.run_if(in_state(Screen::Board)),
+                undo_redo.before(board_shift),
+                // undo spawns and despawns tiles through commands, a move sent the same
+                // frame by a key or the bot has to see the whole board it restored
+                apply_system_buffers.before(board_shift),
+            )
+                .chain(),
+        )
+        .add_system(save_game)
+        // the game over event is sent on the way out of RunState::Playing, so this runs in every state
+        .add_system(unlock_achievements.after(board_shift).after(end_game))
         .add_systems(
             (
                 render_tile_points,
                 board_shift,
                 render_tiles,
-                new_tile_handler,
-                end_game,
+                // the history snapshot taken by board_shift has to see the rng before
+                // the new tile is drawn
+                new_tile_handler.after(board_shift),
+                nudge_board,
+            )
+                // a replay moves the board through the same systems as a live game
+                .distributive_run_if(board_in_play),
+        )
+        .add_systems(
+            (
+                // keys only move the board when no other screen is shown over it
+                board_shift_input
+                    .before(board_shift)
+                    .run_if(in_state(Screen::Board)),
+                autoplay_input.run_if(in_state(Screen::Board)),
+                track_play_time.run_if(in_state(Screen::Board)),
+                // the tiles merged away and spawned by the move have to be in the world,
+                // before that the board can look stuck when it isn't
+                apply_system_buffers
+                    .after(new_tile_handler)
+                    .before(end_game),
+                end_game.after(new_tile_handler),
+                // a winning move that also fills the board shows the win screen first,
+                // keep going then ends up on the game over screen
+                check_win.after(end_game),
             )
                 // Use in_set to run systems when RunState::Playing, so when the state reaches RunState::GameOver,
                 // it ends the game by stopping those systems.
                 .in_set(OnUpdate(RunState::Playing)),
         )
-        .add_systems((game_reset, spawn_tiles).in_schedule(OnEnter(RunState::Playing)))
+        .add_systems(
+            // game_reset picks the seed that spawn_tiles draws from and clears the old board.
+            // The apply_system_buffers system is used so that spawn_tiles system can query
+            // for the board entity produced from the spawn_board system. Normally these all
+            // run in parallel, which is what you typically want.
+            (
+                game_reset,
+                spawn_board,
+                apply_system_buffers,
+                spawn_tiles.run_if(not(resource_exists::<SavedGame>())),
+                restore_saved_game.run_if(resource_exists::<SavedGame>()),
+            )
+                .chain()
+                // skipped when keep going after a win brings us back to RunState::Playing
+                .distributive_run_if(starting_new_game)
+                .in_schedule(OnEnter(RunState::Playing)),
+        )
+        .add_system(
+            finish_resume
+                .after(spawn_tiles)
+                .in_schedule(OnEnter(RunState::Playing)),
+        )
+        .add_systems(
+            (
+                setup_replay,
+                game_reset,
+                mark_recorded,
+                spawn_board,
+                apply_system_buffers,
+                spawn_tiles,
+            )
+                .chain()
+                .in_schedule(OnEnter(RunState::Replaying)),
+        )
+        .add_system(leave_replay.in_schedule(OnExit(RunState::Replaying)))
+        .add_systems(
+            (
+                replay_input.run_if(in_state(Screen::Board)),
+                // a seek counts the moves played, so a step back has to land the same frame
+                replay_playback.before(undo_redo).before(board_shift),
+            )
+                .chain()
+                .in_set(OnUpdate(RunState::Replaying)),
+        )
         .run();
 }
@@ -0,0 +1,210 @@
+use crate::{
+    data::{board_shift::BoardShift, game_settings::GameSettings, spawn_rules::SpawnRules},
+    engine::session::Session,
+};
+
+use super::{observation::Observation, reward::Reward};
+
+/// The game an Env plays and what it pays for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvConfig {
+    pub width: u8,
+    pub height: u8,
+    pub spawn_rules: SpawnRules,
+    pub reward: Reward,
+    // only reported in StepInfo, the episode goes on after a win like keep going does
+    pub target_value: u32,
+}
+
+impl Default for EnvConfig {
+    fn default() -> Self {
+        let settings = GameSettings::default();
+        EnvConfig {
+            width: settings.board_width,
+            height: settings.board_height,
+            spawn_rules: SpawnRules::default(),
+            reward: Reward::default(),
+            target_value: settings.target_value,
+        }
+    }
+}
+
+/// Everything about a step that isn't the observation, reward or done flag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepInfo {
+    // false when the action didn't change the board, which then gets no new tile either
+    pub moved: bool,
+    // points scored by this step and in the whole episode so far
+    pub points: u32,
+    pub score: u32,
+    pub moves: u32,
+    pub highest_tile: u32,
+    pub won: bool,
+    // for the next step, indexed like BoardShift::ALL
+    pub legal_actions: [bool; 4],
+}
+
+/// A Gym-style environment around the same Session the bots and the terminal game play,
+/// so tiles spawn exactly as they do in the game and an episode ends exactly when
+/// end_game would end it. Actions are moves, `BoardShift::ALL` numbers them for agents
+/// that want indices.
+#[derive(Debug, Clone)]
+pub struct Env {
+    config: EnvConfig,
+    session: Session,
+}
+
+impl Env {
+    /// Starts on seed 0, call `reset` to pick the seed of the first episode.
+    pub fn new(config: EnvConfig) -> Self {
+        let session = Session::new(config.width, config.height, config.spawn_rules.clone(), 0);
+        Env { config, session }
+    }
+
+    pub fn config(&self) -> &EnvConfig {
+        &self.config
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// Starts a new episode, the same seed always deals the same opening tiles.
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.session = Session::new(
+            self.config.width,
+            self.config.height,
+            self.config.spawn_rules.clone(),
+            seed,
+        );
+        self.observation()
+    }
+
+    /// Plays `action` and returns the observation after the new tile has spawned, the reward,
+    /// whether the episode is over and the details. An illegal action leaves everything as it was.
+    pub fn step(&mut self, action: BoardShift) -> (Observation, f32, bool, StepInfo) {
+        let outcome = self.session.play(action);
+        let reward = self.config.reward.of(&outcome);
+        let highest_tile = self.session.grid.highest_tile().unwrap_or(0);
+        let info = StepInfo {
+            moved: outcome.changed(),
+            points: outcome.score,
+            score: self.session.score,
+            moves: self.session.moves,
+            highest_tile,
+            won: highest_tile >= self.config.target_value,
+            legal_actions: self.legal_actions(),
+        };
+        (self.observation(), reward, self.is_done(), info)
+    }
+
+    pub fn observation(&self) -> Observation {
+        Observation::from_grid(&self.session.grid)
+    }
+
+    /// Which actions would change the board, indexed like BoardShift::ALL.
+    pub fn legal_actions(&self) -> [bool; 4] {
+        BoardShift::ALL.map(|shift| self.session.grid.clone().apply(shift).changed())
+    }
+
+    /// The game over check from end_game.
+    pub fn is_done(&self) -> bool {
+        !self.session.grid.has_moves()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::position::Position,
+        engine::{grid::Grid, move_outcome::MoveOutcome},
+    };
+
+    fn first_legal(env: &Env) -> Option<BoardShift> {
+        let legal = env.legal_actions();
+        BoardShift::ALL
+            .into_iter()
+            .zip(legal)
+            .find_map(|(shift, legal)| legal.then_some(shift))
+    }
+
+    #[test]
+    fn deals_the_same_tiles_as_the_game() {
+        let mut env = Env::new(EnvConfig::default());
+        let observation = env.reset(42);
+        let session = Session::new(4, 4, SpawnRules::default(), 42);
+        assert_eq!(observation, Observation::from_grid(&session.grid));
+        assert_eq!(env.reset(42), observation);
+    }
+
+    #[test]
+    fn ignores_illegal_actions() {
+        let mut env = Env::new(EnvConfig::default());
+        // the first opening with a side that has nothing to push
+        let (before, illegal) = (0..)
+            .find_map(|seed| {
+                let observation = env.reset(seed);
+                BoardShift::ALL
+                    .into_iter()
+                    .zip(env.legal_actions())
+                    .find_map(|(shift, legal)| (!legal).then_some(shift))
+                    .map(|shift| (observation, shift))
+            })
+            .unwrap();
+        let (after, reward, done, info) = env.step(illegal);
+        assert_eq!(after, before);
+        assert_eq!(reward, 0.0);
+        assert!(!done);
+        assert!(!info.moved);
+        assert_eq!(info.moves, 0);
+    }
+
+    #[test]
+    fn plays_until_no_action_is_legal() {
+        let mut env = Env::new(EnvConfig::default());
+        env.reset(3);
+        let mut total = 0.0;
+        while let Some(action) = first_legal(&env) {
+            let (_, reward, done, info) = env.step(action);
+            assert!(info.moved);
+            total += reward;
+            assert_eq!(done, info.legal_actions == [false; 4]);
+        }
+        assert!(env.is_done());
+        assert_eq!(total, env.session().score as f32);
+    }
+
+    #[test]
+    fn pays_each_kind_of_reward() {
+        // two 4s and two 2s in the bottom row, pushing left makes an 8 and a 4
+        let tiles = [
+            (Position { x: 0, y: 0 }, 4),
+            (Position { x: 1, y: 0 }, 4),
+            (Position { x: 2, y: 0 }, 2),
+            (Position { x: 3, y: 0 }, 2),
+        ];
+        let outcome = Grid::from_tiles(4, 4, tiles).apply(BoardShift::Left);
+        assert_eq!(Reward::ScoreDelta.of(&outcome), 12.0);
+        assert_eq!(Reward::LogMerged.of(&outcome), 5.0);
+        assert_eq!(Reward::Survival.of(&outcome), 1.0);
+        for reward in [Reward::ScoreDelta, Reward::LogMerged, Reward::Survival] {
+            assert_eq!(reward.of(&MoveOutcome::default()), 0.0);
+        }
+    }
+
+    #[test]
+    fn observes_the_board_from_the_top_left() {
+        let tiles = [
+            (Position { x: 0, y: 2 }, 2),
+            (Position { x: 2, y: 0 }, 2048),
+        ];
+        let observation = Observation::from_grid(&Grid::from_tiles(3, 3, tiles));
+        assert_eq!(observation.cells, vec![1, 0, 0, 0, 0, 0, 0, 0, 11]);
+        let encoded = observation.one_hot(12);
+        assert_eq!(encoded.len(), 9 * 12);
+        assert_eq!(encoded[1], 1.0);
+        assert_eq!(encoded[8 * 12 + 11], 1.0);
+        assert_eq!(encoded.iter().sum::<f32>(), 9.0);
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{data::position::Position, engine::grid::Grid};
+
+/// What an agent sees of the board: the exponent of every tile, so 2 is 1 and 2048 is 11,
+/// and 0 for an empty cell. Cells go row by row from the top left, the way the board is drawn.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Observation {
+    pub width: u8,
+    pub height: u8,
+    pub cells: Vec<u8>,
+}
+
+impl Observation {
+    pub fn from_grid(grid: &Grid) -> Self {
+        let (width, height) = (grid.width(), grid.height());
+        let cells = (0..height)
+            .rev()
+            .flat_map(|y| (0..width).map(move |x| Position { x, y }))
+            .map(|pos| {
+                grid.get(pos)
+                    .map_or(0, |value| value.trailing_zeros() as u8)
+            })
+            .collect();
+        Observation {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// The cells one-hot encoded for a network: `planes` values per cell, with a 1 at the
+    /// tile's exponent. Exponents past the last plane go in the last one. Panics on 0 planes,
+    /// which leave nowhere to put the 1.
+    pub fn one_hot(&self, planes: usize) -> Vec<f32> {
+        assert!(planes > 0, "a one-hot encoding needs at least one plane");
+        let mut encoded = vec![0.0; self.cells.len() * planes];
+        for (index, exponent) in self.cells.iter().enumerate() {
+            let plane = usize::from(*exponent).min(planes - 1);
+            encoded[index * planes + plane] = 1.0;
+        }
+        encoded
+    }
+}
@@ -0,0 +1,32 @@
+use crate::engine::move_outcome::MoveOutcome;
+
+/// What an agent is paid for a step. A move that doesn't change the board pays nothing
+/// under any of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reward {
+    /// The points the move scored, the same as the score in the game goes up by.
+    #[default]
+    ScoreDelta,
+    /// The exponent of every tile the move merged, added up, so making a 2048 is worth 11
+    /// instead of 2048 and late merges don't drown out everything else.
+    LogMerged,
+    /// 1 for every move that changed the board, to learn to stay alive as long as possible.
+    Survival,
+}
+
+impl Reward {
+    pub fn of(self, outcome: &MoveOutcome) -> f32 {
+        if !outcome.changed() {
+            return 0.0;
+        }
+        match self {
+            Reward::ScoreDelta => outcome.score as f32,
+            Reward::LogMerged => outcome
+                .merged
+                .iter()
+                .map(|value| value.trailing_zeros() as f32)
+                .sum(),
+            Reward::Survival => 1.0,
+        }
+    }
+}
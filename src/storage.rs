@@ -0,0 +1,41 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+// Where records and other files that outlive a run are kept,
+// e.g. ~/.local/share/boxes on Linux. None if the platform has no data directory.
+pub fn data_dir() -> Option<PathBuf> {
+    // tests never touch the player's own files
+    if cfg!(test) {
+        let dir = format!("boxes-test-{}", std::process::id());
+        return Some(std::env::temp_dir().join(dir));
+    }
+    dirs::data_dir().map(|dir| dir.join("boxes"))
+}
+
+// seconds since the Unix epoch, used to stamp games and file names
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> io::Result<T> {
+    let bytes = fs::read(path)?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+// Write to a temporary file next to the target and rename it over the target, so a crash
+// halfway through never leaves a truncated file behind.
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, serde_json::to_vec_pretty(value)?)?;
+    fs::rename(temp_path, path)
+}
@@ -1,81 +1,107 @@
+use std::collections::HashMap;
+
 use bevy::prelude::*;
-use itertools::Itertools;
 
-use crate::data::{
-    board::Board, board_shift::BoardShift, game::Game, new_tile_event::NewTileEvent,
-    points::Points, position::Position,
+use crate::{
+    data::{
+        board::Board,
+        board_shift_event::BoardShiftEvent,
+        game::Game,
+        game_event::GameEvent,
+        game_rng::GameRng,
+        invalid_move_event::InvalidMoveEvent,
+        move_history::{HistoryEntry, MoveHistory},
+        new_tile_event::NewTileEvent,
+        points::Points,
+        position::Position,
+        run_state::RunState,
+    },
+    engine::grid::Grid,
 };
 
+#[allow(clippy::too_many_arguments)]
 pub fn board_shift(
     mut commands: Commands,
-    input: Res<Input<KeyCode>>,
+    mut shift_reader: EventReader<BoardShiftEvent>,
     mut tiles: Query<(Entity, &mut Position, &mut Points)>,
     query_board: Query<&Board>,
     mut tile_writer: EventWriter<NewTileEvent>, // allows us to send events to the queue
+    mut invalid_move_writer: EventWriter<InvalidMoveEvent>,
+    mut game_event_writer: EventWriter<GameEvent>,
     mut game: ResMut<Game>,
+    game_rng: Res<GameRng>,
+    mut history: ResMut<MoveHistory>,
+    run_state: Res<State<RunState>>,
 ) {
     let board = query_board.single();
 
-    // using just_pressed is useful because it only match once per press
-    let shift_direction = input
-        .get_just_pressed()
-        .find_map(|key_code| BoardShift::try_from(key_code).ok());
+    // the tiles are only up to date for the first move, the rest of the events are dropped
+    let shift_direction = shift_reader.iter().next().map(|event| event.board_shift);
 
     if let Some(board_shift) = shift_direction {
-        // the tiles query is mutable so we use iter_mut()
-        // sort by row, then by column (sorted_by is from the itertools crate)
-        // Ordering is an Enum in the std crate
-        // Ord is a trait from the std crate, requires the cmp (compare method), which compares to values
-        // of the given type. u8 already has an Ord impl
-        let mut it = tiles
-            .iter_mut()
-            // determine if any two y values in a tiles positions component are equal (same row)
-            // if they are not equal, pass the return value back to sorted_by because it will be an
-            // ordering variant.
-            .sorted_by(|a, b| board_shift.sort(&a.1, &b.1))
-            .peekable(); // allows us to look at the next value in the iterator
+        // the slide/merge rules live in the engine, we only copy the result back onto the entities
+        let mut grid = Grid::from_tiles(
+            board.width,
+            board.height,
+            tiles.iter().map(|(_, pos, points)| (*pos, points.value)),
+        );
+        let before = grid.clone();
+        let outcome = grid.apply(board_shift);
 
-        // use the underscore to tell the compiler to figure out the type
-        // dbg!(it.collect::<Vec<_>>());
+        // nothing slid or merged, so the move doesn't count and no tile is spawned
+        if !outcome.changed() {
+            invalid_move_writer.send(InvalidMoveEvent { board_shift });
+            return;
+        }
 
-        let mut column: u8 = 0;
-        while let Some(mut tile) = it.next() {
-            board_shift.set_column_position(board.size, &mut tile.1, column);
-            if let Some(tile_next) = it.peek() {
-                if board_shift.get_row_position(&tile.1)
-                    != board_shift.get_row_position(&tile_next.1)
-                {
-                    // different rows, don't merge
-                    column = 0;
-                } else if tile.2.value != tile_next.2.value {
-                    // different values, don't merge
-                    column = column + 1;
-                } else {
-                    // merge
-                    let real_next_tile = it
-                        .next()
-                        .expect("A peeked tile should always exist when we ...");
-                    tile.2.value = tile.2.value + real_next_tile.2.value;
+        history.record(HistoryEntry {
+            board_shift,
+            grid: before,
+            score: game.score,
+            won: game.won,
+            rng: game_rng.rng.clone(),
+            moves: outcome.moves.clone(),
+        });
 
-                    game.score += tile.2.value;
+        // look up which entity was sitting on each cell before the shift
+        let entities: HashMap<Position, Entity> = tiles
+            .iter()
+            .map(|(entity, pos, _)| (*pos, entity))
+            .collect();
 
-                    commands.entity(real_next_tile.0).despawn_recursive();
+        for tile_move in outcome.moves.iter() {
+            let entity = entities[&tile_move.from];
+            if tile_move.absorbed {
+                commands.entity(entity).despawn_recursive();
+                continue;
+            }
 
-                    if let Some(future) = it.peek() {
-                        if board_shift.get_row_position(&tile.1)
-                            != board_shift.get_row_position(&future.1)
-                        {
-                            column = 0;
-                        } else {
-                            column = column + 1;
-                        }
-                    }
-                }
+            let (_, mut position, mut points) = tiles
+                .get_mut(entity)
+                .expect("every moved tile should have an entity");
+            let value = grid
+                .get(tile_move.to)
+                .expect("a moved tile should land on an occupied cell");
+            // only write on change so render_tiles doesn't ease tiles that stayed put
+            if *position != tile_move.to {
+                *position = tile_move.to;
+            }
+            if points.value != value {
+                points.value = value;
             }
         }
-        tile_writer.send(NewTileEvent);
-        if game.best_score < game.score {
-            game.best_score = game.score;
+
+        game.score += outcome.score;
+        game.moves += 1;
+        // watching a replay doesn't count as playing
+        if run_state.0 != RunState::Replaying {
+            if !game.autoplayed {
+                game.best_score = game.best_score.max(game.score);
+            }
+            game_event_writer.send(GameEvent::Moved {
+                highest_tile: grid.highest_tile().unwrap_or(0),
+            });
         }
+        tile_writer.send(NewTileEvent { grid });
     }
 }
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+use crate::data::{game::Game, game_settings::GameSettings, points::Points, run_state::RunState};
+
+pub fn check_win(
+    tiles: Query<&Points>,
+    settings: Res<GameSettings>,
+    mut game: ResMut<Game>,
+    mut run_state: ResMut<NextState<RunState>>,
+) {
+    // only the first tile to reach the target counts, after that the player keeps going
+    if game.won {
+        return;
+    }
+
+    if tiles
+        .iter()
+        .any(|points| points.value >= settings.target_value)
+    {
+        game.won = true;
+        run_state.set(RunState::Won);
+    }
+}
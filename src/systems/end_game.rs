@@ -1,44 +1,32 @@
-use std::{collections::HashMap, ops::Range};
-
 use bevy::prelude::*;
 
-use crate::data::{board::Board, points::Points, position::Position, run_state::RunState};
+use crate::{
+    data::{
+        board::Board, game_event::GameEvent, points::Points, position::Position,
+        run_state::RunState,
+    },
+    engine::grid::Grid,
+};
 
 pub fn end_game(
     tiles: Query<(&Position, &Points)>,
     query_board: Query<&Board>,
     mut run_state: ResMut<NextState<RunState>>,
+    mut game_event_writer: EventWriter<GameEvent>,
 ) {
     let board = query_board.single();
 
-    if tiles.iter().len() == 16 {
-        let map: HashMap<&Position, &Points> = tiles.iter().collect();
-
-        let neighbor_points = [(-1, 0), (0, 1), (1, 0), (0, -1)];
-        let board_range: Range<i8> = 0..(board.size as i8);
-
-        let has_move = tiles.iter().any(|(Position { x, y }, value)| {
-            neighbor_points
-                .iter()
-                .filter_map(|(x2, y2)| {
-                    let new_x = *x as i8 - x2;
-                    let new_y = *y as i8 - y2;
-
-                    if !board_range.contains(&new_x) || !board_range.contains(&new_y) {
-                        return None;
-                    }
-
-                    map.get(&Position {
-                        x: new_x.try_into().unwrap(),
-                        y: new_y.try_into().unwrap(),
-                    })
-                })
-                .any(|&v| v == value)
+    let grid = Grid::from_tiles(
+        board.width,
+        board.height,
+        tiles.iter().map(|(pos, points)| (*pos, points.value)),
+    );
+
+    if !grid.has_moves() {
+        info!("game over");
+        run_state.set(RunState::GameOver);
+        game_event_writer.send(GameEvent::GameOver {
+            highest_tile: grid.highest_tile().unwrap_or(0),
         });
-
-        if !has_move {
-            dbg!("game over!");
-            run_state.set(RunState::GameOver);
-        }
     }
 }
@@ -0,0 +1,162 @@
+use bevy::prelude::*;
+
+use crate::{
+    data::{board::Board, game::Game, run_state::RunState},
+    systems::{
+        game_reset::{game_reset, starting_new_game},
+        record_game::record_game,
+        write_replay::write_replay,
+    },
+};
+
+// Records the game and writes its replay once per ending, whichever way the game is left:
+// reaching the game over screen, or starting a new game straight from the win screen.
+pub struct FinishGamePlugin;
+
+impl Plugin for FinishGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (record_game, write_replay, mark_recorded)
+                .chain()
+                .distributive_run_if(game_unrecorded)
+                .in_schedule(OnEnter(RunState::GameOver)),
+        )
+        .add_systems(
+            (record_game, write_replay, mark_recorded)
+                .chain()
+                // game_reset clears the board these read from
+                .before(game_reset)
+                .distributive_run_if(starting_new_game)
+                .distributive_run_if(game_unrecorded)
+                .in_schedule(OnEnter(RunState::Playing)),
+        );
+    }
+}
+
+// false once this ending was recorded, and before the first board is spawned
+pub fn game_unrecorded(game: Res<Game>, boards: Query<(), With<Board>>) -> bool {
+    !game.recorded && !boards.is_empty()
+}
+
+// also run after a replay is set up, watching one isn't playing a game
+pub fn mark_recorded(mut game: ResMut<Game>) {
+    game.recorded = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::{
+            game_rng::GameRng, game_settings::GameSettings, move_history::MoveHistory,
+            points::Points, position::Position, records::Records, replay::Replay,
+            spawn_rules::SpawnRules,
+        },
+        engine::session::Session,
+        storage,
+    };
+
+    // a game on the 4x4 board seeded with 1, with the given tiles
+    fn app(game: Game, tiles: impl IntoIterator<Item = (Position, u32)>) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_state::<RunState>()
+            .add_plugin(FinishGamePlugin)
+            .add_system(
+                game_reset
+                    .run_if(starting_new_game)
+                    .in_schedule(OnEnter(RunState::Playing)),
+            )
+            .insert_resource(Game::default())
+            .insert_resource(Records::default())
+            .insert_resource(SpawnRules::default())
+            .insert_resource(GameRng::new(Some(1)))
+            .insert_resource(MoveHistory::default())
+            .insert_resource(GameSettings::default());
+        // the first update enters RunState::Playing, with no board to record yet
+        app.update();
+        app.insert_resource(game);
+        app.world.spawn(Board::new(4, 4));
+        for (pos, value) in tiles {
+            app.world.spawn((pos, Points { value }));
+        }
+        app
+    }
+
+    fn tiles() -> [(Position, u32); 2] {
+        [
+            (Position { x: 0, y: 0 }, 2048),
+            (Position { x: 1, y: 0 }, 4),
+        ]
+    }
+
+    fn go_to(app: &mut App, run_state: RunState) {
+        app.world
+            .resource_mut::<NextState<RunState>>()
+            .set(run_state);
+        app.update();
+    }
+
+    #[test]
+    fn records_a_new_game_from_the_win_screen() {
+        let mut app = app(
+            Game {
+                score: 20000,
+                won: true,
+                ..default()
+            },
+            tiles(),
+        );
+        go_to(&mut app, RunState::Won);
+        go_to(&mut app, RunState::Playing);
+
+        let records = app.world.resource::<Records>();
+        assert_eq!(records.stats.games_played, 1);
+        assert_eq!(records.stats.games_won, 1);
+        assert_eq!(records.stats.win_rate(), 1.0);
+        assert_eq!(records.leaderboard(None)[0].score, 20000);
+        // game_reset cleared the board after it was recorded
+        assert_eq!(app.world.resource::<Game>().score, 0);
+    }
+
+    #[test]
+    fn records_a_game_over_once() {
+        let mut app = app(
+            Game {
+                score: 300,
+                ..default()
+            },
+            tiles(),
+        );
+        go_to(&mut app, RunState::GameOver);
+        assert_eq!(app.world.resource::<Records>().stats.games_played, 1);
+        go_to(&mut app, RunState::Playing);
+
+        let records = app.world.resource::<Records>();
+        assert_eq!(records.stats.games_played, 1);
+        assert_eq!(records.leaderboard(None).len(), 1);
+        assert_eq!(records.stats.win_rate(), 0.0);
+    }
+
+    #[test]
+    fn writes_the_replay_of_a_new_game_from_the_win_screen() {
+        // the board as the seed starts it, so the replay reproduces it
+        let grid = Session::new(4, 4, SpawnRules::default(), 1).grid;
+        let tiles = grid
+            .positions()
+            .filter_map(|pos| grid.get(pos).map(|value| (pos, value)));
+        let mut app = app(
+            Game {
+                won: true,
+                started_at: 11,
+                ..default()
+            },
+            tiles,
+        );
+        go_to(&mut app, RunState::Won);
+        go_to(&mut app, RunState::Playing);
+
+        let path = storage::data_dir().unwrap().join("replays/11-1.json");
+        assert_eq!(Replay::load(&path).unwrap().simulate().grid, grid);
+    }
+}
@@ -1,14 +1,64 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 
-use crate::data::{game::Game, position::Position};
+use crate::{
+    data::{
+        board::Board,
+        game::Game,
+        game_rng::GameRng,
+        game_settings::GameSettings,
+        move_history::MoveHistory,
+        position::Position,
+        records::{Records, RuleSet},
+        spawn_rules::SpawnRules,
+    },
+    storage,
+};
 
+#[allow(clippy::too_many_arguments)]
 pub fn game_reset(
     mut commands: Commands,
     tiles: Query<Entity, With<Position>>,
+    boards: Query<Entity, With<Board>>,
     mut game: ResMut<Game>,
+    mut game_rng: ResMut<GameRng>,
+    mut history: ResMut<MoveHistory>,
+    settings: Res<GameSettings>,
+    spawn_rules: Res<SpawnRules>,
+    records: Res<Records>,
 ) {
     for entity in tiles.iter() {
         commands.entity(entity).despawn_recursive();
     }
+    // spawn_board builds a fresh board, possibly with a different size
+    for entity in boards.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
     game.score = 0;
+    game.moves = 0;
+    game.started_at = storage::unix_time();
+    game.play_time = Duration::ZERO;
+    game.won = false;
+    game.recorded = false;
+    game.in_stats = false;
+    game.autoplayed = false;
+    // spawn_board builds the board from the same settings, so this is the matching best score
+    game.best_score = records.best_score(&RuleSet::new(
+        settings.board_width,
+        settings.board_height,
+        &spawn_rules,
+    ));
+    game_rng.new_game();
+    history.clear();
+}
+
+// run condition for the systems that set up a new game, false when coming back
+// to RunState::Playing to continue the current one
+pub fn starting_new_game(game: Res<Game>) -> bool {
+    !game.resuming
+}
+
+pub fn finish_resume(mut game: ResMut<Game>) {
+    game.resuming = false;
 }
@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+
+use crate::data::{
+    autoplay::Autoplay, board_shift::BoardShift, board_shift_event::BoardShiftEvent,
+    history_event::HistoryEvent, replay_control::ReplayControl,
+};
+
+pub fn board_shift_input(
+    input: Res<Input<KeyCode>>,
+    mut shift_writer: EventWriter<BoardShiftEvent>,
+) {
+    // using just_pressed is useful because it only match once per press
+    let shift_direction = input
+        .get_just_pressed()
+        .find_map(|key_code| BoardShift::try_from(key_code).ok());
+
+    if let Some(board_shift) = shift_direction {
+        shift_writer.send(BoardShiftEvent { board_shift });
+    }
+}
+
+pub fn history_input(input: Res<Input<KeyCode>>, mut history_writer: EventWriter<HistoryEvent>) {
+    if input.just_pressed(KeyCode::Z) {
+        history_writer.send(HistoryEvent::Undo);
+    } else if input.just_pressed(KeyCode::Y) {
+        history_writer.send(HistoryEvent::Redo);
+    }
+}
+
+pub fn replay_input(input: Res<Input<KeyCode>>, mut control_writer: EventWriter<ReplayControl>) {
+    let controls = [
+        (KeyCode::Space, ReplayControl::TogglePlay),
+        (KeyCode::Right, ReplayControl::StepForward),
+        (KeyCode::Left, ReplayControl::StepBack),
+        (KeyCode::Up, ReplayControl::Faster),
+        (KeyCode::Down, ReplayControl::Slower),
+        (KeyCode::Home, ReplayControl::Seek(0)),
+        (KeyCode::End, ReplayControl::Seek(usize::MAX)),
+    ];
+    for (key_code, control) in controls {
+        if input.just_pressed(key_code) {
+            control_writer.send(control);
+        }
+    }
+}
+
+pub fn autoplay_input(input: Res<Input<KeyCode>>, mut autoplay: ResMut<Autoplay>) {
+    if input.just_pressed(KeyCode::P) {
+        autoplay.enabled = !autoplay.enabled;
+    }
+}
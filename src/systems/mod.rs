@@ -1,9 +1,20 @@
 pub mod board_shift;
+pub mod check_win;
 pub mod end_game;
+pub mod finish_game;
 pub mod game_reset;
+pub mod keyboard_input;
 pub mod new_tile_handler;
+pub mod nudge_board;
+pub mod record_game;
 pub mod render_tile_points;
 pub mod render_tiles;
+pub mod replay_playback;
+pub mod save_game;
 pub mod setup;
 pub mod spawn_board;
 pub mod spawn_tiles;
+pub mod track_play_time;
+pub mod undo_redo;
+pub mod unlock_achievements;
+pub mod write_replay;
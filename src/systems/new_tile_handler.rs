@@ -1,9 +1,8 @@
 use bevy::prelude::*;
-use itertools::Itertools;
-use rand::prelude::*;
 
 use crate::data::{
-    board::Board, font_spec::FontSpec, new_tile_event::NewTileEvent, position::Position,
+    board::Board, font_spec::FontSpec, game_rng::GameRng, new_tile_event::NewTileEvent,
+    spawn_rules::SpawnRules,
 };
 
 use super::spawn_tiles::spawn_tile;
@@ -12,32 +11,93 @@ pub fn new_tile_handler(
     mut tile_reader: EventReader<NewTileEvent>,
     mut commands: Commands,
     query_board: Query<&Board>,
-    tiles: Query<&Position>,
     font_spec: Res<FontSpec>,
+    mut game_rng: ResMut<GameRng>,
+    spawn_rules: Res<SpawnRules>,
 ) {
     let board = query_board.single();
 
     // get all the events we haven't handled yet
-    for _event in tile_reader.iter() {
-        // insert new tile
-        let mut rng = rand::thread_rng();
-        let possible_position: Option<Position> = (0..board.size)
-            .cartesian_product(0..board.size)
-            // filter out all tiles that already have a number
-            .filter_map(|tile_pos| {
-                let new_pos = Position {
-                    x: tile_pos.0,
-                    y: tile_pos.1,
-                };
-                match tiles.iter().find(|&&pos| pos == new_pos) {
-                    Some(_) => None,
-                    None => Some(new_pos),
-                }
+    for event in tile_reader.iter() {
+        // the tile entities still include the ones merged away this frame, the grid from
+        // board_shift doesn't, so the tiles land where Session puts them
+        let mut grid = event.grid.clone();
+        for _ in 0..spawn_rules.tiles_per_move {
+            if let Some((pos, value)) = grid.spawn(&spawn_rules, &mut game_rng.rng) {
+                spawn_tile(&mut commands, board, &font_spec, pos, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::{
+            board_shift::BoardShift, board_shift_event::BoardShiftEvent, game::Game,
+            game_event::GameEvent, invalid_move_event::InvalidMoveEvent, move_history::MoveHistory,
+            points::Points, position::Position, run_state::RunState,
+        },
+        engine::{grid::Grid, session::Session},
+        systems::{board_shift::board_shift, spawn_tiles::spawn_tiles},
+    };
+
+    // the window's tiles after every move, with the seed the app was started with
+    fn play(seed: u64, shifts: impl IntoIterator<Item = BoardShift>) -> Vec<(Grid, u32)> {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_state::<RunState>()
+            .add_event::<BoardShiftEvent>()
+            .add_event::<NewTileEvent>()
+            .add_event::<InvalidMoveEvent>()
+            .add_event::<GameEvent>()
+            .insert_resource(FontSpec {
+                family: Handle::default(),
             })
-            .choose(&mut rng); // pick a random tile from the filtered list
+            .insert_resource(SpawnRules::default())
+            .insert_resource(GameRng::new(Some(seed)))
+            .init_resource::<Game>()
+            .init_resource::<MoveHistory>()
+            .add_startup_system(spawn_tiles)
+            .add_system(board_shift)
+            .add_system(new_tile_handler.after(board_shift));
+        app.world.spawn(Board::new(4, 4));
+        app.update();
+
+        let mut played = Vec::new();
+        for board_shift in shifts {
+            app.world.send_event(BoardShiftEvent { board_shift });
+            app.update();
+            let tiles = app
+                .world
+                .query::<(&Position, &Points)>()
+                .iter(&app.world)
+                .map(|(pos, points)| (*pos, points.value))
+                .collect::<Vec<_>>();
+            let score = app.world.resource::<Game>().score;
+            played.push((Grid::from_tiles(4, 4, tiles), score));
+        }
+        played
+    }
 
-        if let Some(pos) = possible_position {
-            spawn_tile(&mut commands, board, &font_spec, pos);
+    // the same seed and moves give the same tiles as Session, merges included
+    #[test]
+    fn spawns_where_session_does() {
+        let shifts = [
+            BoardShift::Left,
+            BoardShift::Down,
+            BoardShift::Right,
+            BoardShift::Down,
+        ];
+        for seed in 0..5 {
+            let mut session = Session::new(4, 4, SpawnRules::default(), seed);
+            let played = play(seed, shifts.into_iter().cycle().take(60));
+            for (board_shift, (grid, score)) in shifts.into_iter().cycle().zip(played) {
+                session.play(board_shift);
+                assert_eq!(grid, session.grid, "seed {seed}");
+                assert_eq!(score, session.score, "seed {seed}");
+            }
         }
     }
 }
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use bevy_easings::{Ease, EaseFunction, EasingType};
+
+use crate::data::{board::Board, invalid_move_event::InvalidMoveEvent, position::Position};
+
+const NUDGE_DISTANCE: f32 = 6.0;
+const NUDGE_MILLIS: u64 = 60;
+
+// bump the board and its tiles towards an invalid shift and back, so the key press
+// still gets some feedback even though nothing moved
+pub fn nudge_board(
+    mut commands: Commands,
+    mut invalid_moves: EventReader<InvalidMoveEvent>,
+    query_board: Query<(Entity, &Board)>,
+    tiles: Query<(Entity, &Position)>,
+) {
+    // several presses in one frame only need one nudge
+    let Some(event) = invalid_moves.iter().last() else {
+        return;
+    };
+    let offset = (event.board_shift.direction() * NUDGE_DISTANCE).extend(0.0);
+
+    let (board_entity, board) = query_board.single();
+    nudge(&mut commands, board_entity, Transform::default(), offset);
+
+    for (entity, pos) in tiles.iter() {
+        let rest = Transform::from_translation(board.cell_position_to_physical(*pos).extend(2.0));
+        nudge(&mut commands, entity, rest, offset);
+    }
+}
+
+fn nudge(commands: &mut Commands, entity: Entity, rest: Transform, offset: Vec3) {
+    let mut nudged = rest;
+    nudged.translation += offset;
+    commands.entity(entity).insert(
+        // start from the resting transform so repeated nudges don't drift
+        rest.ease_to(
+            nudged,
+            EaseFunction::QuadraticOut,
+            EasingType::Once {
+                duration: std::time::Duration::from_millis(NUDGE_MILLIS),
+            },
+        )
+        .ease_to(
+            rest,
+            EaseFunction::QuadraticIn,
+            EasingType::Once {
+                duration: std::time::Duration::from_millis(NUDGE_MILLIS),
+            },
+        ),
+    );
+}
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+use crate::data::{
+    board::Board,
+    game::Game,
+    game_rng::GameRng,
+    move_history::MoveHistory,
+    points::Points,
+    records::{GameSummary, LeaderboardEntry, Records, RuleSet},
+    spawn_rules::SpawnRules,
+};
+use crate::storage;
+
+// runs whenever a game ends, see FinishGamePlugin, and writes the updated records to disk
+pub fn record_game(
+    tiles: Query<&Points>,
+    query_board: Query<&Board>,
+    spawn_rules: Res<SpawnRules>,
+    game_rng: Res<GameRng>,
+    history: Res<MoveHistory>,
+    mut game: ResMut<Game>,
+    mut records: ResMut<Records>,
+) {
+    if game.autoplayed {
+        return;
+    }
+
+    let board = query_board.single();
+    let rules = RuleSet::new(board.width, board.height, &spawn_rules);
+    let highest_tile = tiles.iter().map(|points| points.value).max().unwrap_or(0);
+
+    records.submit_score(&rules, game.score);
+    records.submit_to_leaderboard(LeaderboardEntry {
+        score: game.score,
+        highest_tile,
+        moves: game.moves,
+        width: board.width,
+        height: board.height,
+        seed: game_rng.seed,
+        started_at: game.started_at,
+        finished_at: storage::unix_time(),
+        play_time: Some(game.play_time.as_secs()),
+    });
+    // a game picked back up with undo already counts towards the stats from its first ending
+    if !game.in_stats {
+        records.add_game(&GameSummary {
+            score: game.score,
+            highest_tile,
+            moves: game.moves,
+            won: game.won,
+            shifts: history.moves().collect(),
+        });
+        game.in_stats = true;
+    }
+
+    if let Err(err) = records.save() {
+        error!("could not save records: {err}");
+    }
+}
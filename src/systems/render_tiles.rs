@@ -16,8 +16,7 @@ pub fn render_tiles(
 ) {
     let board = query_board.single();
     for (entity, transform, pos) in tiles.iter_mut() {
-        let x = board.cell_position_to_physical(pos.x);
-        let y = board.cell_position_to_physical(pos.y);
+        let Vec2 { x, y } = board.cell_position_to_physical(*pos);
         commands.entity(entity).insert(transform.ease_to(
             // final pos
             Transform::from_xyz(x, y, transform.translation.z),
@@ -0,0 +1,101 @@
+use std::cmp::Ordering;
+
+use bevy::prelude::*;
+
+use crate::data::{
+    board_shift_event::BoardShiftEvent, game_rng::GameRng, game_settings::GameSettings,
+    history_event::HistoryEvent, move_history::MoveHistory, replay_control::ReplayControl,
+    replay_player::ReplayPlayer, spawn_rules::SpawnRules,
+};
+
+// runs before game_reset so the new game uses the replay's board, spawns and seed
+pub fn setup_replay(
+    mut player: ResMut<ReplayPlayer>,
+    mut settings: ResMut<GameSettings>,
+    mut spawn_rules: ResMut<SpawnRules>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    player.stashed = Some((settings.clone(), spawn_rules.clone()));
+    let replay = &player.replay;
+    settings.board_width = replay.width;
+    settings.board_height = replay.height;
+    *spawn_rules = replay.spawn_rules.clone();
+    game_rng.set_next_seed(replay.seed);
+}
+
+// runs on the way out of RunState::Replaying, so the next game is played with the
+// player's own board size and spawn rules again
+pub fn leave_replay(
+    mut player: ResMut<ReplayPlayer>,
+    mut settings: ResMut<GameSettings>,
+    mut spawn_rules: ResMut<SpawnRules>,
+) {
+    if let Some((stashed_settings, stashed_rules)) = player.stashed.take() {
+        *settings = stashed_settings;
+        *spawn_rules = stashed_rules;
+    }
+}
+
+// Feeds the recorded moves to board_shift one at a time. Stepping back is an undo,
+// which restores the rng so stepping forward again spawns the same tile. Seeking steps
+// every frame until the replay is at the target move.
+pub fn replay_playback(
+    time: Res<Time>,
+    mut controls: EventReader<ReplayControl>,
+    mut player: ResMut<ReplayPlayer>,
+    history: Res<MoveHistory>,
+    mut shift_writer: EventWriter<BoardShiftEvent>,
+    mut history_writer: EventWriter<HistoryEvent>,
+) {
+    let mut step_forward = false;
+    let mut step_back = false;
+    for control in controls.iter() {
+        match control {
+            ReplayControl::TogglePlay => {
+                player.playing = !player.playing;
+                player.seek_to = None;
+            }
+            ReplayControl::StepForward => {
+                player.playing = false;
+                player.seek_to = None;
+                step_forward = true;
+            }
+            ReplayControl::StepBack => {
+                player.playing = false;
+                player.seek_to = None;
+                step_back = true;
+            }
+            ReplayControl::Seek(target) => {
+                player.playing = false;
+                player.seek_to = Some((*target).min(player.replay.moves.len()));
+            }
+            ReplayControl::Faster => player.change_speed(2.0),
+            ReplayControl::Slower => player.change_speed(0.5),
+        }
+    }
+
+    if player.playing && player.timer.tick(time.delta()).just_finished() {
+        step_forward = true;
+    }
+
+    let position = history.moves().count();
+    if let Some(target) = player.seek_to {
+        match target.cmp(&position) {
+            Ordering::Less => step_back = true,
+            Ordering::Greater => step_forward = true,
+            Ordering::Equal => player.seek_to = None,
+        }
+    }
+
+    if step_back {
+        history_writer.send(HistoryEvent::Undo);
+    } else if step_forward {
+        match player.replay.moves.get(position) {
+            Some(board_shift) => shift_writer.send(BoardShiftEvent {
+                board_shift: *board_shift,
+            }),
+            // reached the end of the game
+            None => player.playing = false,
+        }
+    }
+}
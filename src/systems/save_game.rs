@@ -0,0 +1,218 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, window::WindowCloseRequested};
+
+use crate::{
+    data::{
+        board::Board,
+        font_spec::FontSpec,
+        game::Game,
+        game_rng::GameRng,
+        game_settings::GameSettings,
+        move_history::{HistoryEntry, MoveHistory},
+        points::Points,
+        position::Position,
+        run_state::RunState,
+        saved_game::SavedGame,
+        spawn_rules::SpawnRules,
+    },
+    engine::{grid::Grid, session::Session},
+};
+
+use super::spawn_tiles::spawn_tile;
+
+// takes the place of spawn_tiles when the app was launched with a saved game
+#[allow(clippy::too_many_arguments)]
+pub fn restore_saved_game(
+    mut commands: Commands,
+    saved: Res<SavedGame>,
+    query_board: Query<&Board>,
+    font_spec: Res<FontSpec>,
+    mut game: ResMut<Game>,
+    mut game_rng: ResMut<GameRng>,
+    mut history: ResMut<MoveHistory>,
+    spawn_rules: Res<SpawnRules>,
+    settings: Res<GameSettings>,
+) {
+    let board = query_board.single();
+    for (pos, value) in saved.tiles.iter() {
+        spawn_tile(&mut commands, board, &font_spec, *pos, *value);
+    }
+
+    game.score = saved.score;
+    game.moves = saved.moves;
+    game.won = saved.won;
+    game.started_at = saved.started_at;
+    game.play_time = Duration::from_secs(saved.play_time);
+    game.autoplayed = saved.autoplayed;
+    game_rng.seed = saved.seed;
+    game_rng.rng = saved.rng.clone();
+
+    // play the moves again from the seed to get the undo history back
+    let mut session = Session::new(saved.width, saved.height, spawn_rules.clone(), saved.seed);
+    for board_shift in saved.shifts.iter() {
+        let grid = session.grid.clone();
+        let entry = HistoryEntry {
+            board_shift: *board_shift,
+            won: grid
+                .highest_tile()
+                .is_some_and(|value| value >= settings.target_value),
+            grid,
+            score: session.score,
+            rng: session.rng.clone(),
+            moves: session.play(*board_shift).moves,
+        };
+        history.record(entry);
+    }
+    let saved_grid = Grid::from_tiles(saved.width, saved.height, saved.tiles.iter().copied());
+    if session.grid != saved_grid || session.score != saved.score {
+        // older save or different spawn rules, the board itself is still fine
+        warn!("the saved moves don't lead to the saved board, undo starts from here");
+        history.clear();
+    }
+
+    // only the first game is restored
+    commands.remove_resource::<SavedGame>();
+}
+
+// F5 saves the current game, closing the window saves it too
+#[allow(clippy::too_many_arguments)]
+pub fn save_game(
+    input: Res<Input<KeyCode>>,
+    mut close_requests: EventReader<WindowCloseRequested>,
+    tiles: Query<(&Position, &Points)>,
+    query_board: Query<&Board>,
+    game: Res<Game>,
+    game_rng: Res<GameRng>,
+    history: Res<MoveHistory>,
+    run_state: Res<State<RunState>>,
+) {
+    let closing = close_requests.iter().count() > 0;
+    if !closing && !input.just_pressed(KeyCode::F5) {
+        return;
+    }
+
+    let result = match run_state.0 {
+        RunState::Playing | RunState::Won => {
+            let board = query_board.single();
+            SavedGame {
+                width: board.width,
+                height: board.height,
+                tiles: tiles
+                    .iter()
+                    .map(|(pos, points)| (*pos, points.value))
+                    .collect(),
+                score: game.score,
+                moves: game.moves,
+                won: game.won,
+                started_at: game.started_at,
+                autoplayed: game.autoplayed,
+                play_time: game.play_time.as_secs(),
+                seed: game_rng.seed,
+                rng: game_rng.rng.clone(),
+                shifts: history.moves().collect(),
+            }
+            .save()
+        }
+        // nothing left to resume, the next launch starts a new game
+        RunState::GameOver => SavedGame::delete(),
+        // keep whatever game was saved before the replay
+        RunState::Replaying => Ok(()),
+    };
+
+    if let Err(err) = result {
+        error!("could not save the current game: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data::{
+            board_shift::BoardShift, board_shift_event::BoardShiftEvent, game_event::GameEvent,
+            invalid_move_event::InvalidMoveEvent, new_tile_event::NewTileEvent,
+        },
+        systems::{
+            board_shift::board_shift, new_tile_handler::new_tile_handler, spawn_tiles::spawn_tiles,
+        },
+    };
+
+    // a 4x4 board with the resources the game systems need, `setup` fills it on the first update
+    fn app<M>(seed: Option<u64>, setup: impl IntoSystemConfig<M>) -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_state::<RunState>()
+            .add_event::<BoardShiftEvent>()
+            .add_event::<NewTileEvent>()
+            .add_event::<InvalidMoveEvent>()
+            .add_event::<GameEvent>()
+            .add_event::<WindowCloseRequested>()
+            .init_resource::<Input<KeyCode>>()
+            .insert_resource(FontSpec {
+                family: Handle::default(),
+            })
+            .insert_resource(SpawnRules::default())
+            .insert_resource(GameSettings::default())
+            .insert_resource(GameRng::new(seed))
+            .init_resource::<Game>()
+            .init_resource::<MoveHistory>()
+            .add_startup_system(setup)
+            .add_system(board_shift)
+            .add_system(new_tile_handler.after(board_shift))
+            .add_system(save_game);
+        app.world.spawn(Board::new(4, 4));
+        app
+    }
+
+    fn grid(app: &mut App) -> Grid {
+        let tiles = app
+            .world
+            .query::<(&Position, &Points)>()
+            .iter(&app.world)
+            .map(|(pos, points)| (*pos, points.value))
+            .collect::<Vec<_>>();
+        Grid::from_tiles(4, 4, tiles)
+    }
+
+    // a resumed game keeps its undo history, so its replay and achievements still see every move
+    #[test]
+    fn restores_a_saved_game_with_its_moves() {
+        let mut playing = app(Some(9), spawn_tiles);
+        playing.update();
+        let shifts = [BoardShift::Left, BoardShift::Down, BoardShift::Right];
+        for board_shift in shifts.into_iter().cycle().take(30) {
+            playing.world.send_event(BoardShiftEvent { board_shift });
+            playing.update();
+        }
+        playing
+            .world
+            .resource_mut::<Input<KeyCode>>()
+            .press(KeyCode::F5);
+        playing.update();
+
+        let saved = SavedGame::load().expect("F5 should have saved the game");
+        let mut resumed = app(None, restore_saved_game);
+        resumed.insert_resource(saved);
+        resumed.update();
+
+        let played: Vec<_> = playing.world.resource::<MoveHistory>().moves().collect();
+        let restored: Vec<_> = resumed.world.resource::<MoveHistory>().moves().collect();
+        assert!(played.len() > 20);
+        assert_eq!(restored, played);
+        assert_eq!(grid(&mut resumed), grid(&mut playing));
+        assert_eq!(
+            resumed.world.resource::<Game>().score,
+            playing.world.resource::<Game>().score
+        );
+
+        // and the next tiles are the ones the unsaved game would have spawned
+        for app in [&mut playing, &mut resumed] {
+            app.world.send_event(BoardShiftEvent {
+                board_shift: BoardShift::Up,
+            });
+            app.update();
+        }
+        assert_eq!(grid(&mut resumed), grid(&mut playing));
+    }
+}
@@ -1,12 +1,17 @@
-use crate::{colors, Board};
+use crate::{
+    colors,
+    data::{game_settings::GameSettings, position::Position},
+    Board,
+};
 use bevy::prelude::*;
 use itertools::Itertools;
 
 pub const TILE_SIZE: f32 = 40.0;
 pub const TILE_SPACER: f32 = 10.0;
 
-pub fn spawn_board(mut commands: Commands) {
-    let board = Board::new(4);
+// runs at the start of every game so a newly chosen board size takes effect
+pub fn spawn_board(mut commands: Commands, settings: Res<GameSettings>) {
+    let board = Board::new(settings.board_width, settings.board_height);
 
     commands
         .spawn(SpriteBundle {
@@ -18,17 +23,17 @@ pub fn spawn_board(mut commands: Commands) {
             ..default()
         })
         .with_children(|builder| {
-            for tile in (0..board.size).cartesian_product(0..board.size) {
+            for (x, y) in (0..board.width).cartesian_product(0..board.height) {
                 builder.spawn(SpriteBundle {
                     sprite: Sprite {
                         color: colors::TILE_PLACEHOLDER,
                         custom_size: Some(Vec2::new(TILE_SIZE, TILE_SIZE)),
                         ..default()
                     },
-                    transform: Transform::from_xyz(
-                        board.cell_position_to_physical(tile.0),
-                        board.cell_position_to_physical(tile.1),
-                        1.0,
+                    transform: Transform::from_translation(
+                        board
+                            .cell_position_to_physical(Position { x, y })
+                            .extend(1.0),
                     ),
                     ..default()
                 });
@@ -1,33 +1,31 @@
 use bevy::prelude::*;
-use itertools::Itertools;
-use rand::prelude::*;
 
 use crate::{
     colors,
     data::{
-        board::Board, font_spec::FontSpec, points::Points, position::Position, tile_text::TileText,
+        board::Board, font_spec::FontSpec, game_rng::GameRng, points::Points, position::Position,
+        spawn_rules::SpawnRules, tile_text::TileText,
     },
+    engine::grid::Grid,
 };
 
 use super::spawn_board::TILE_SIZE;
 
 pub fn spawn_tiles(
-    mut commands: Commands,     // to spawn the tile sprites
-    query_board: Query<&Board>, // query for the board component to get the board size
-    font_spec: Res<FontSpec>,   // access to the FontSpec resource
+    mut commands: Commands,        // to spawn the tile sprites
+    query_board: Query<&Board>,    // query for the board component to get the board size
+    font_spec: Res<FontSpec>,      // access to the FontSpec resource
+    mut game_rng: ResMut<GameRng>, // seeded rng so games can be reproduced
+    spawn_rules: Res<SpawnRules>,  // how many tiles to start with and which values they get
 ) {
     let board = query_board.single(); // single will panic if != 1
 
-    let mut rng = rand::thread_rng(); // rng to arbitrarily choose two locations in the grid
-    let starting_tiles: Vec<(u8, u8)> = (0..board.size)
-        .cartesian_product(0..board.size) // generate all possible grid locations
-        .choose_multiple(&mut rng, 2); // pick two random tiles
-
-    // destructure the x & y values from the tuples of the starting tiles
-    // the iter() gives us references to the elements in a  Rust vec
-    for (x, y) in starting_tiles.iter() {
-        let pos = Position { x: *x, y: *y };
-        spawn_tile(&mut commands, board, &font_spec, pos);
+    // game_reset already despawned the old tiles, so we start from an empty grid
+    let mut grid = Grid::new(board.width, board.height);
+    for _ in 0..spawn_rules.starting_tiles {
+        if let Some((pos, value)) = grid.spawn(&spawn_rules, &mut game_rng.rng) {
+            spawn_tile(&mut commands, board, &font_spec, pos, value);
+        }
     }
 }
 
@@ -36,7 +34,8 @@ pub fn spawn_tile(
     board: &Board,
     font_spec: &Res<FontSpec>,
     pos: Position,
-) {
+    value: u32,
+) -> Entity {
     commands
         .spawn(SpriteBundle {
             sprite: Sprite {
@@ -44,10 +43,8 @@ pub fn spawn_tile(
                 custom_size: Some(Vec2::new(TILE_SIZE, TILE_SIZE)),
                 ..default()
             },
-            transform: Transform::from_xyz(
-                board.cell_position_to_physical(pos.x),
-                board.cell_position_to_physical(pos.y),
-                2.0,
+            transform: Transform::from_translation(
+                board.cell_position_to_physical(pos).extend(2.0),
             ),
             ..default()
         })
@@ -56,7 +53,7 @@ pub fn spawn_tile(
                 .spawn(Text2dBundle {
                     text: Text::from_section(
                         // text uses sections to define updatable areas of content
-                        "2", // defaault value for tiles
+                        value.to_string(),
                         TextStyle {
                             font: font_spec.family.clone(), // family is a handle, so we clone it (only cloning the id)
                             font_size: 40.0,
@@ -69,6 +66,7 @@ pub fn spawn_tile(
                 })
                 .insert(TileText); // insert component so we can find it later
         })
-        .insert(Points { value: 2 })
-        .insert(pos);
+        .insert(Points { value })
+        .insert(pos)
+        .id()
 }
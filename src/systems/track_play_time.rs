@@ -0,0 +1,9 @@
+use bevy::prelude::*;
+
+use crate::data::game::Game;
+
+// runs while the board is shown in RunState::Playing, so the leaderboard times how long a
+// game was played rather than how long ago it was started
+pub fn track_play_time(time: Res<Time>, mut game: ResMut<Game>) {
+    game.play_time += time.delta();
+}
@@ -0,0 +1,103 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::prelude::*;
+
+use crate::data::{
+    board::Board, board_shift_event::BoardShiftEvent, font_spec::FontSpec, game::Game,
+    game_rng::GameRng, history_event::HistoryEvent, move_history::MoveHistory, points::Points,
+    position::Position, run_state::RunState,
+};
+
+use super::spawn_tiles::spawn_tile;
+
+#[allow(clippy::too_many_arguments)]
+pub fn undo_redo(
+    mut commands: Commands,
+    mut history_reader: EventReader<HistoryEvent>,
+    mut shift_writer: EventWriter<BoardShiftEvent>,
+    mut history: ResMut<MoveHistory>,
+    mut tiles: Query<(Entity, &mut Position, &mut Points)>,
+    query_board: Query<&Board>,
+    font_spec: Res<FontSpec>,
+    mut game: ResMut<Game>,
+    mut game_rng: ResMut<GameRng>,
+    run_state: Res<State<RunState>>,
+    mut next_state: ResMut<NextState<RunState>>,
+) {
+    // like board_shift, only one change per frame can see up to date tiles
+    let Some(event) = history_reader.iter().last() else {
+        return;
+    };
+
+    match (event, &run_state.0) {
+        // the win screen has to be answered first
+        (_, RunState::Won) => {}
+        // redo is just the same move again, the restored rng makes it spawn the same tile
+        (HistoryEvent::Redo, RunState::Playing | RunState::Replaying) => {
+            if let Some(board_shift) = history.redo() {
+                shift_writer.send(BoardShiftEvent { board_shift });
+            }
+        }
+        (HistoryEvent::Redo, RunState::GameOver) => {}
+        (HistoryEvent::Undo, _) => {
+            let Some(entry) = history.undo() else {
+                return;
+            };
+            let board = query_board.single();
+
+            let entities: HashMap<Position, Entity> = tiles
+                .iter()
+                .map(|(entity, pos, _)| (*pos, entity))
+                .collect();
+
+            // every tile that isn't where a tile slid or merged to was spawned after the move
+            let landed: HashSet<Position> = entry
+                .moves
+                .iter()
+                .filter(|tile_move| !tile_move.absorbed)
+                .map(|tile_move| tile_move.to)
+                .collect();
+            for (pos, entity) in entities.iter() {
+                if !landed.contains(pos) {
+                    commands.entity(*entity).despawn_recursive();
+                }
+            }
+
+            // run the move backwards, render_tiles eases every tile back to where it came from
+            for tile_move in entry.moves.iter() {
+                let value = entry
+                    .grid
+                    .get(tile_move.from)
+                    .expect("every moved tile was on the grid before the move");
+                if tile_move.absorbed {
+                    // bring back the merged away tile, starting from where it disappeared
+                    let entity = spawn_tile(&mut commands, board, &font_spec, tile_move.to, value);
+                    commands.entity(entity).insert(tile_move.from);
+                } else {
+                    let (_, mut position, mut points) = tiles
+                        .get_mut(entities[&tile_move.to])
+                        .expect("every landed tile should have an entity");
+                    if *position != tile_move.from {
+                        *position = tile_move.from;
+                    }
+                    if points.value != value {
+                        points.value = value;
+                    }
+                }
+            }
+
+            game.score = entry.score;
+            game.moves = game.moves.saturating_sub(1);
+            game.won = entry.won;
+            game_rng.rng = entry.rng.clone();
+
+            // undoing the last move of a finished game picks it back up, to be recorded
+            // again when it ends
+            if run_state.0 == RunState::GameOver {
+                game.resuming = true;
+                game.recorded = false;
+                next_state.set(RunState::Playing);
+            }
+        }
+    }
+}
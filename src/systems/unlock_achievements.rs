@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+
+use crate::data::{
+    achievement_unlocked_event::AchievementUnlockedEvent,
+    achievements::{Achievements, GameProgress, ACHIEVEMENTS},
+    board::Board,
+    game::Game,
+    game_event::GameEvent,
+    game_settings::GameSettings,
+    move_history::MoveHistory,
+};
+
+// checks every locked achievement against the game whenever something happens in it
+pub fn unlock_achievements(
+    mut game_events: EventReader<GameEvent>,
+    query_board: Query<&Board>,
+    game: Res<Game>,
+    history: Res<MoveHistory>,
+    settings: Res<GameSettings>,
+    mut achievements: ResMut<Achievements>,
+    mut unlocked_writer: EventWriter<AchievementUnlockedEvent>,
+) {
+    // a bot's game doesn't earn anything
+    if game.autoplayed {
+        game_events.clear();
+        return;
+    }
+
+    let mut newly_unlocked = false;
+    for event in game_events.iter() {
+        let board = query_board.single();
+        let (highest_tile, game_over) = match *event {
+            GameEvent::Moved { highest_tile } => (highest_tile, false),
+            GameEvent::GameOver { highest_tile } => (highest_tile, true),
+        };
+        let progress = GameProgress {
+            width: board.width,
+            height: board.height,
+            score: game.score,
+            moves: game.moves,
+            highest_tile,
+            target_value: settings.target_value,
+            shifts_used: history.moves().collect(),
+            game_over,
+        };
+
+        for achievement in ACHIEVEMENTS {
+            if achievements.unlocked_at(achievement.id).is_none()
+                && achievement.condition.is_met(&progress)
+            {
+                achievements.unlock(achievement.id);
+                unlocked_writer.send(AchievementUnlockedEvent { achievement });
+                newly_unlocked = true;
+            }
+        }
+    }
+
+    if newly_unlocked {
+        if let Err(err) = achievements.save() {
+            error!("could not save achievements: {err}");
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+use crate::{
+    data::{
+        board::Board,
+        game::Game,
+        game_rng::GameRng,
+        move_history::MoveHistory,
+        points::Points,
+        position::Position,
+        replay::{Replay, REPLAY_VERSION},
+        spawn_rules::SpawnRules,
+    },
+    engine::grid::Grid,
+};
+
+// runs whenever a game ends, see FinishGamePlugin. board_shift and new_tile_handler are the only systems that
+// change the board, so the seed plus the moves kept for undo are the whole game
+pub fn write_replay(
+    tiles: Query<(&Position, &Points)>,
+    query_board: Query<&Board>,
+    spawn_rules: Res<SpawnRules>,
+    game: Res<Game>,
+    game_rng: Res<GameRng>,
+    history: Res<MoveHistory>,
+) {
+    let board = query_board.single();
+    let replay = Replay {
+        version: REPLAY_VERSION,
+        width: board.width,
+        height: board.height,
+        spawn_rules: spawn_rules.clone(),
+        seed: game_rng.seed,
+        moves: history.moves().collect(),
+        score: game.score,
+        started_at: game.started_at,
+    };
+
+    // e.g. a game resumed from a save that was written without its moves
+    let session = replay.simulate();
+    let board_grid = Grid::from_tiles(
+        board.width,
+        board.height,
+        tiles.iter().map(|(pos, points)| (*pos, points.value)),
+    );
+    if session.grid != board_grid || session.score != game.score {
+        warn!("not writing a replay, the recorded moves don't reproduce this game");
+        return;
+    }
+
+    match replay.save() {
+        Ok(Some(path)) => info!("replay written to {}", path.display()),
+        Ok(None) => {}
+        Err(err) => error!("could not write replay: {err}"),
+    }
+}
@@ -1,17 +1,49 @@
-use crate::{colors, FontSpec, Game, RunState};
-use bevy::prelude::*;
+use crate::{
+    colors,
+    data::{
+        autoplay::Autoplay,
+        game_settings::{BoardDimension, GameSettings},
+        history_event::HistoryEvent,
+        screen::Screen,
+    },
+    FontSpec, Game, GameRng, RunState,
+};
+use bevy::{ecs::system::EntityCommands, prelude::*};
 
+mod achievements_screen;
+mod hint;
+mod leaderboard;
+mod replay_controls;
+mod stats_screen;
 mod styles;
+mod toasts;
+mod win_screen;
 
 pub struct GameUiPlugin;
 
 impl Plugin for GameUiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(setup_ui).add_systems((
-            scoreboard,
-            button_interaction_system,
-            button_text_system,
-        ));
+        app.add_startup_system(setup_ui)
+            .add_systems((
+                scoreboard,
+                seed_display,
+                board_size_display,
+                button_interaction_system,
+                game_button_system,
+                board_size_button_system,
+                history_button_system,
+                autoplay_button_system,
+                autoplay_label_system.run_if(resource_changed::<Autoplay>()),
+                screen_button_system,
+                button_text_system,
+            ))
+            .add_plugin(win_screen::WinScreenPlugin)
+            .add_plugin(leaderboard::LeaderboardPlugin)
+            .add_plugin(stats_screen::StatsScreenPlugin)
+            .add_plugin(achievements_screen::AchievementsScreenPlugin)
+            .add_plugin(toasts::ToastsPlugin)
+            .add_plugin(hint::HintPlugin)
+            .add_plugin(replay_controls::ReplayControlsPlugin);
     }
 }
 
@@ -21,6 +53,30 @@ pub struct ScoreDisplay;
 #[derive(Component)]
 pub struct BestScoreDisplay;
 
+#[derive(Component)]
+pub struct SeedDisplay;
+
+#[derive(Component)]
+pub struct BoardSizeDisplay;
+
+// the End Game / New Game button
+#[derive(Component)]
+pub struct GameButton;
+
+#[derive(Component)]
+pub struct HistoryButton(HistoryEvent);
+
+#[derive(Component)]
+pub struct AutoplayButton;
+
+// opens one of the screens shown over the board
+#[derive(Component)]
+pub struct ScreenButton(Screen);
+
+// steps one side of the board for the next game by the contained amount
+#[derive(Component)]
+pub struct BoardSizeButton(BoardDimension, i8);
+
 fn setup_ui(mut commands: Commands, font_spec: Res<FontSpec>) {
     commands
         .spawn(NodeBundle {
@@ -34,14 +90,36 @@ fn setup_ui(mut commands: Commands, font_spec: Res<FontSpec>) {
             ..default()
         })
         .with_children(|parent| {
-            parent.spawn(TextBundle::from_section(
-                "2048",
-                TextStyle {
-                    font: font_spec.family.clone(),
-                    font_size: 40.0,
-                    color: Color::WHITE,
-                },
-            ));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "2048",
+                        TextStyle {
+                            font: font_spec.family.clone(),
+                            font_size: 40.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                    // the seed lets a game be replayed with `--seed`
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "<seed>",
+                            TextStyle {
+                                font: font_spec.family.clone(),
+                                font_size: 15.0,
+                                color: Color::WHITE,
+                            },
+                        ),
+                        SeedDisplay,
+                    ));
+                });
 
             parent
                 .spawn(NodeBundle {
@@ -123,31 +201,157 @@ fn setup_ui(mut commands: Commands, font_spec: Res<FontSpec>) {
                 });
 
             parent
-                .spawn(ButtonBundle {
+                .spawn(NodeBundle {
                     style: Style {
-                        size: Size::new(Val::Px(130.0), Val::Px(50.0)),
-                        justify_content: JustifyContent::Center,
                         align_items: AlignItems::Center,
+                        gap: Size::all(Val::Px(10.0)),
                         ..default()
                     },
                     ..default()
                 })
                 .with_children(|parent| {
-                    parent.spawn(TextBundle {
-                        text: Text::from_section(
-                            "Button",
+                    // board size picker for the next game, width then height
+                    spawn_button(
+                        parent,
+                        &font_spec,
+                        40.0,
+                        "-",
+                        BoardSizeButton(BoardDimension::Width, -1),
+                    );
+                    spawn_button(
+                        parent,
+                        &font_spec,
+                        40.0,
+                        "+",
+                        BoardSizeButton(BoardDimension::Width, 1),
+                    );
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "<size>",
                             TextStyle {
                                 font: font_spec.family.clone(),
                                 font_size: 20.0,
-                                color: Color::rgb(0.9, 0.9, 0.9),
+                                color: Color::WHITE,
                             },
                         ),
-                        ..default()
-                    });
+                        BoardSizeDisplay,
+                    ));
+                    spawn_button(
+                        parent,
+                        &font_spec,
+                        40.0,
+                        "-",
+                        BoardSizeButton(BoardDimension::Height, -1),
+                    );
+                    spawn_button(
+                        parent,
+                        &font_spec,
+                        40.0,
+                        "+",
+                        BoardSizeButton(BoardDimension::Height, 1),
+                    );
+
+                    spawn_button(
+                        parent,
+                        &font_spec,
+                        80.0,
+                        "Undo",
+                        HistoryButton(HistoryEvent::Undo),
+                    );
+                    spawn_button(
+                        parent,
+                        &font_spec,
+                        80.0,
+                        "Redo",
+                        HistoryButton(HistoryEvent::Redo),
+                    );
+                    spawn_button(parent, &font_spec, 110.0, "Autoplay", AutoplayButton);
+                    spawn_button(parent, &font_spec, 80.0, "Hint", hint::HintButton);
+                    spawn_button(parent, &font_spec, 130.0, "Button", GameButton);
+                    spawn_button(
+                        parent,
+                        &font_spec,
+                        90.0,
+                        "Scores",
+                        ScreenButton(Screen::Leaderboard),
+                    );
+                    spawn_button(
+                        parent,
+                        &font_spec,
+                        90.0,
+                        "Stats",
+                        ScreenButton(Screen::Stats),
+                    );
+                    spawn_button(
+                        parent,
+                        &font_spec,
+                        90.0,
+                        "Awards",
+                        ScreenButton(Screen::Achievements),
+                    );
                 });
         });
 }
 
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    font_spec: &FontSpec,
+    width: f32,
+    label: &str,
+    marker: impl Component,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Px(width), Val::Px(50.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    label,
+                    TextStyle {
+                        font: font_spec.family.clone(),
+                        font_size: 20.0,
+                        color: Color::rgb(0.9, 0.9, 0.9),
+                    },
+                ),
+                ..default()
+            });
+        });
+}
+
+// A dimmed full-screen node for a screen shown over the board. Everything else on the
+// screen goes in its children, so despawning the node with `marker` removes it in one go.
+fn spawn_overlay<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    marker: impl Component,
+) -> EntityCommands<'w, 's, 'a> {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                gap: Size::all(Val::Px(20.0)),
+                ..default()
+            },
+            background_color: BackgroundColor(colors::OVERLAY),
+            ..default()
+        },
+        marker,
+    ))
+}
+
 // handle score text
 fn scoreboard(
     game: Res<Game>,
@@ -167,17 +371,37 @@ fn scoreboard(
     }
 }
 
+fn seed_display(game_rng: Res<GameRng>, mut query_seed: Query<&mut Text, With<SeedDisplay>>) {
+    if !game_rng.is_changed() {
+        return;
+    }
+    let mut text = query_seed.single_mut();
+    if let Some(section) = text.sections.first_mut() {
+        section.value = format!("seed {}", game_rng.seed);
+    }
+}
+
+fn board_size_display(
+    settings: Res<GameSettings>,
+    mut query_size: Query<&mut Text, With<BoardSizeDisplay>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    let mut text = query_size.single_mut();
+    if let Some(section) = text.sections.first_mut() {
+        section.value = format!("{}x{}", settings.board_width, settings.board_height);
+    }
+}
+
+// filter entities with interaction components (Changed<Interaction>) that have
+// changed since the last interaction of our button interaction system that are
+// buttons (With<Button>).
+type ChangedButtons = (Changed<Interaction>, With<Button>);
+
 fn button_interaction_system(
-    mut interaction_query: Query<
-        // since the ButtonBundle has a BackgroundColor, we can utilize that in our button query
-        (&Interaction, &mut BackgroundColor),
-        // filter entities with interaction components (Changed<Interaction>) that have
-        // changed since the last interaction of our button interaction system that are
-        // buttons (With<Button>).
-        (Changed<Interaction>, With<Button>),
-    >,
-    run_state: Res<State<RunState>>,
-    mut next_state: ResMut<NextState<RunState>>,
+    // since the ButtonBundle has a BackgroundColor, we can utilize that in our button query
+    mut interaction_query: Query<(&Interaction, &mut BackgroundColor), ChangedButtons>,
 ) {
     // loop over interaction query with iter_mut() (common pattern)
     // alternative to the single_mut() we have been using
@@ -185,16 +409,6 @@ fn button_interaction_system(
         match interaction {
             Interaction::Clicked => {
                 *color = colors::button::PRESSED.into(); // into() used for Color into BackgroundColor
-
-                // our state is a tuple containing the RunState enum
-                match run_state.0 {
-                    RunState::Playing => {
-                        next_state.set(RunState::GameOver);
-                    }
-                    RunState::GameOver => {
-                        next_state.set(RunState::Playing);
-                    }
-                }
             }
             Interaction::Hovered => {
                 *color = colors::button::HOVERED.into();
@@ -206,8 +420,88 @@ fn button_interaction_system(
     }
 }
 
+fn game_button_system(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<GameButton>)>,
+    run_state: Res<State<RunState>>,
+    mut next_state: ResMut<NextState<RunState>>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            // our state is a tuple containing the RunState enum
+            match run_state.0 {
+                RunState::Playing => {
+                    next_state.set(RunState::GameOver);
+                }
+                RunState::Won | RunState::GameOver | RunState::Replaying => {
+                    next_state.set(RunState::Playing);
+                }
+            }
+        }
+    }
+}
+
+fn board_size_button_system(
+    interaction_query: Query<(&Interaction, &BoardSizeButton), Changed<Interaction>>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, BoardSizeButton(dimension, delta)) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            settings.change_board_size(*dimension, *delta);
+        }
+    }
+}
+
+fn history_button_system(
+    interaction_query: Query<(&Interaction, &HistoryButton), Changed<Interaction>>,
+    mut history_writer: EventWriter<HistoryEvent>,
+) {
+    for (interaction, HistoryButton(event)) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            history_writer.send(*event);
+        }
+    }
+}
+
+fn autoplay_button_system(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<AutoplayButton>)>,
+    mut autoplay: ResMut<Autoplay>,
+) {
+    for interaction in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            autoplay.enabled = !autoplay.enabled;
+        }
+    }
+}
+
+// names what the next click does, whether autoplay was toggled here or with P
+fn autoplay_label_system(
+    autoplay: Res<Autoplay>,
+    button_query: Query<&Children, With<AutoplayButton>>,
+    mut text_query: Query<&mut Text>,
+) {
+    let children = button_query.single();
+    let first_child_entity = children
+        .first()
+        .expect("expect button to have a first child");
+    let mut text = text_query.get_mut(*first_child_entity).unwrap();
+    if let Some(section) = text.sections.first_mut() {
+        section.value = if autoplay.enabled { "Stop" } else { "Autoplay" }.to_string();
+    }
+}
+
+fn screen_button_system(
+    interaction_query: Query<(&Interaction, &ScreenButton), Changed<Interaction>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    for (interaction, ScreenButton(screen)) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            next_screen.set(screen.clone());
+        }
+    }
+}
+
 fn button_text_system(
-    button_query: Query<&Children, With<Button>>,
+    button_query: Query<&Children, With<GameButton>>,
     mut text_query: Query<&mut Text>,
     run_state: Res<State<RunState>>,
 ) {
@@ -222,10 +516,57 @@ fn button_text_system(
                 section.value = "End Game".to_string();
             }
         }
-        RunState::GameOver => {
+        RunState::Won | RunState::GameOver | RunState::Replaying => {
             if let Some(section) = text.sections.first_mut() {
                 section.value = "New Game".to_string();
             }
         }
     }
 }
+
+// year-month-day in UTC, converted by hand so we don't need a date crate
+fn format_date(unix_time: u64) -> String {
+    let days = unix_time / 86_400 + 719_468; // days since 0000-03-01
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // months counted from March, so the leap day falls at the end of the year
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+    format!("{year}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_date;
+
+    #[test]
+    fn formats_the_epoch() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(86_399), "1970-01-01");
+    }
+
+    #[test]
+    fn formats_leap_days() {
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_709_164_800), "2024-02-29");
+        assert_eq!(format_date(1_709_251_200), "2024-03-01");
+        // 2100 is a century but not a multiple of 400, so it has no leap day
+        assert_eq!(format_date(4_107_456_000), "2100-02-28");
+        assert_eq!(format_date(4_107_542_400), "2100-03-01");
+    }
+
+    #[test]
+    fn formats_the_turn_of_a_year() {
+        assert_eq!(format_date(1_704_067_199), "2023-12-31");
+        assert_eq!(format_date(1_704_067_200), "2024-01-01");
+    }
+}
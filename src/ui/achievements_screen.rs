@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+
+use super::{format_date, spawn_button, spawn_overlay, ScreenButton};
+use crate::{
+    data::{
+        achievements::{Achievements, ACHIEVEMENTS},
+        screen::Screen,
+    },
+    FontSpec,
+};
+
+pub struct AchievementsScreenPlugin;
+
+impl Plugin for AchievementsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setup_achievements_screen.in_schedule(OnEnter(Screen::Achievements)))
+            .add_system(despawn_achievements_screen.in_schedule(OnExit(Screen::Achievements)));
+    }
+}
+
+#[derive(Component)]
+struct AchievementsScreen;
+
+fn setup_achievements_screen(
+    mut commands: Commands,
+    font_spec: Res<FontSpec>,
+    achievements: Res<Achievements>,
+) {
+    let text_style = |font_size, color| TextStyle {
+        font: font_spec.family.clone(),
+        font_size,
+        color,
+    };
+    let unlocked_count = ACHIEVEMENTS
+        .iter()
+        .filter(|achievement| achievements.unlocked_at(achievement.id).is_some())
+        .count();
+
+    spawn_overlay(&mut commands, AchievementsScreen).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            format!("Achievements {unlocked_count}/{}", ACHIEVEMENTS.len()),
+            text_style(40.0, Color::WHITE),
+        ));
+
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    gap: Size::all(Val::Px(8.0)),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                for achievement in ACHIEVEMENTS {
+                    let unlocked_at = achievements.unlocked_at(achievement.id);
+                    // locked achievements are greyed out
+                    let color = match unlocked_at {
+                        Some(_) => Color::WHITE,
+                        None => Color::GRAY,
+                    };
+                    let status = match unlocked_at {
+                        Some(unlocked_at) => format_date(unlocked_at),
+                        None => "locked".to_string(),
+                    };
+                    parent.spawn(NodeBundle::default()).with_children(|parent| {
+                        let cells = [
+                            (achievement.name, 200.0, 20.0),
+                            (achievement.description, 500.0, 18.0),
+                            (status.as_str(), 110.0, 18.0),
+                        ];
+                        for (text, width, font_size) in cells {
+                            parent
+                                .spawn(NodeBundle {
+                                    style: Style {
+                                        size: Size::width(Val::Px(width)),
+                                        ..default()
+                                    },
+                                    ..default()
+                                })
+                                .with_children(|parent| {
+                                    parent.spawn(TextBundle::from_section(
+                                        text,
+                                        text_style(font_size, color),
+                                    ));
+                                });
+                        }
+                    });
+                }
+            });
+
+        spawn_button(
+            parent,
+            &font_spec,
+            90.0,
+            "Close",
+            ScreenButton(Screen::Board),
+        );
+    });
+}
+
+fn despawn_achievements_screen(
+    mut commands: Commands,
+    screens: Query<Entity, With<AchievementsScreen>>,
+) {
+    for entity in screens.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,164 @@
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use futures_lite::future;
+
+use crate::{
+    ai::expectimax::{Expectimax, MoveEstimate},
+    data::{
+        board::Board, board_shift::BoardShift, points::Points, position::Position,
+        spawn_rules::SpawnRules,
+    },
+    engine::grid::Grid,
+    FontSpec, RunState,
+};
+
+pub struct HintPlugin;
+
+impl Plugin for HintPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            (hint_button_system, receive_hint, clear_hint)
+                .chain()
+                .in_set(OnUpdate(RunState::Playing)),
+        )
+        .add_system(despawn_hint.in_schedule(OnExit(RunState::Playing)));
+    }
+}
+
+// a bit deeper than autoplay, one hint can take a moment longer than a move
+const HINT_DEPTH: u32 = 3;
+
+#[derive(Component)]
+pub struct HintButton;
+
+// the arrow over the board, kept until the board it was worked out for changes
+#[derive(Component)]
+struct Hint(Grid);
+
+// a hint being worked out on the AsyncComputeTaskPool for the board in `grid`, like
+// BotPlugin's moves, dropping the task cancels it
+#[derive(Component)]
+struct PendingHint {
+    task: Task<Option<MoveEstimate>>,
+    grid: Grid,
+}
+
+fn current_grid(board: &Board, tiles: &Query<(&Position, &Points)>) -> Grid {
+    Grid::from_tiles(
+        board.width,
+        board.height,
+        tiles.iter().map(|(pos, points)| (*pos, points.value)),
+    )
+}
+
+fn hint_button_system(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<HintButton>)>,
+    tiles: Query<(&Position, &Points)>,
+    query_board: Query<&Board>,
+    hints: Query<&Hint>,
+    pending: Query<&PendingHint>,
+    spawn_rules: Res<SpawnRules>,
+) {
+    if !interaction_query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked)
+    {
+        return;
+    }
+
+    // hints for another board are cleared by receive_hint and clear_hint
+    let grid = current_grid(query_board.single(), &tiles);
+    let shown = hints.iter().any(|Hint(hint_grid)| *hint_grid == grid);
+    if shown || pending.iter().any(|pending_hint| pending_hint.grid == grid) {
+        return;
+    }
+    let expectimax = Expectimax::new(HINT_DEPTH, spawn_rules.clone());
+    let task_grid = grid.clone();
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        expectimax
+            .evaluate_moves(&task_grid)
+            .into_iter()
+            .max_by(|a, b| a.value.total_cmp(&b.value))
+    });
+    commands.spawn(PendingHint { task, grid });
+}
+
+// shows a finished hint, unless the board moved on while it was being worked out
+fn receive_hint(
+    mut commands: Commands,
+    mut pending: Query<(Entity, &mut PendingHint)>,
+    tiles: Query<(&Position, &Points)>,
+    query_board: Query<&Board>,
+    font_spec: Res<FontSpec>,
+) {
+    for (entity, mut pending_hint) in pending.iter_mut() {
+        if pending_hint.grid != current_grid(query_board.single(), &tiles) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let Some(best) = future::block_on(future::poll_once(&mut pending_hint.task)) else {
+            continue;
+        };
+        commands.entity(entity).despawn();
+        if let Some(best) = best {
+            spawn_hint(&mut commands, &font_spec, best, pending_hint.grid.clone());
+        }
+    }
+}
+
+fn spawn_hint(commands: &mut Commands, font_spec: &FontSpec, best: MoveEstimate, grid: Grid) {
+    let arrow = match best.board_shift {
+        BoardShift::Up => "↑",
+        BoardShift::Down => "↓",
+        BoardShift::Left => "←",
+        BoardShift::Right => "→",
+    };
+    let style = |font_size| TextStyle {
+        font: font_spec.family.clone(),
+        font_size,
+        color: Color::rgba(1.0, 1.0, 1.0, 0.8),
+    };
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_sections([
+                TextSection::new(format!("{arrow}\n"), style(120.0)),
+                TextSection::new(
+                    format!("+{:.0} in {HINT_DEPTH} moves", best.points),
+                    style(20.0),
+                ),
+            ])
+            .with_alignment(TextAlignment::Center),
+            // above the tiles, which sit at 2.0 with their text at 3.0
+            transform: Transform::from_xyz(0.0, 0.0, 10.0),
+            ..default()
+        },
+        Hint(grid),
+    ));
+}
+
+// a move or an undo makes the hint stale
+fn clear_hint(
+    mut commands: Commands,
+    tiles: Query<(&Position, &Points)>,
+    query_board: Query<&Board>,
+    hints: Query<(Entity, &Hint)>,
+) {
+    for (entity, Hint(grid)) in hints.iter() {
+        if *grid != current_grid(query_board.single(), &tiles) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+// leaving the game drops the arrow and any hint still being worked out
+type HintOrPending = Or<(With<Hint>, With<PendingHint>)>;
+
+fn despawn_hint(mut commands: Commands, hints: Query<Entity, HintOrPending>) {
+    for entity in hints.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
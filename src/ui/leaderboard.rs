@@ -0,0 +1,219 @@
+use bevy::prelude::*;
+
+use super::{format_date, spawn_button, spawn_overlay, ScreenButton};
+use crate::{
+    data::{
+        records::{LeaderboardEntry, Records},
+        screen::Screen,
+    },
+    FontSpec,
+};
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LeaderboardFilter>()
+            .add_system(setup_leaderboard.in_schedule(OnEnter(Screen::Leaderboard)))
+            .add_system(despawn_leaderboard.in_schedule(OnExit(Screen::Leaderboard)))
+            .add_systems(
+                (
+                    filter_button_system,
+                    render_leaderboard.after(filter_button_system),
+                )
+                    .in_set(OnUpdate(Screen::Leaderboard)),
+            );
+    }
+}
+
+// board size the table is narrowed down to, None shows every size
+#[derive(Resource, Default)]
+struct LeaderboardFilter(Option<(u8, u8)>);
+
+#[derive(Component)]
+struct LeaderboardScreen;
+
+// rows are rebuilt inside this node whenever the filter changes
+#[derive(Component)]
+struct LeaderboardTable;
+
+#[derive(Component)]
+struct LeaderboardTitle;
+
+#[derive(Component)]
+struct FilterButton(Option<(u8, u8)>);
+
+const COLUMNS: [(&str, f32); 8] = [
+    ("#", 40.0),
+    ("Score", 90.0),
+    ("Tile", 70.0),
+    ("Moves", 70.0),
+    ("Time", 80.0),
+    ("Size", 60.0),
+    ("Seed", 200.0),
+    ("Date", 110.0),
+];
+
+fn setup_leaderboard(mut commands: Commands, font_spec: Res<FontSpec>, records: Res<Records>) {
+    spawn_overlay(&mut commands, LeaderboardScreen).with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                "<title>",
+                TextStyle {
+                    font: font_spec.family.clone(),
+                    font_size: 40.0,
+                    color: Color::WHITE,
+                },
+            ),
+            LeaderboardTitle,
+        ));
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    gap: Size::all(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                spawn_button(parent, &font_spec, 60.0, "All", FilterButton(None));
+                for (width, height) in records.leaderboard_sizes() {
+                    spawn_button(
+                        parent,
+                        &font_spec,
+                        60.0,
+                        &format!("{width}x{height}"),
+                        FilterButton(Some((width, height))),
+                    );
+                }
+            });
+        parent.spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    gap: Size::all(Val::Px(5.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            LeaderboardTable,
+        ));
+        spawn_button(
+            parent,
+            &font_spec,
+            90.0,
+            "Close",
+            ScreenButton(Screen::Board),
+        );
+    });
+}
+
+fn despawn_leaderboard(mut commands: Commands, screens: Query<Entity, With<LeaderboardScreen>>) {
+    for entity in screens.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn filter_button_system(
+    interaction_query: Query<(&Interaction, &FilterButton), Changed<Interaction>>,
+    mut filter: ResMut<LeaderboardFilter>,
+) {
+    for (interaction, FilterButton(size)) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            filter.0 = *size;
+        }
+    }
+}
+
+fn render_leaderboard(
+    mut commands: Commands,
+    font_spec: Res<FontSpec>,
+    records: Res<Records>,
+    filter: Res<LeaderboardFilter>,
+    // only set on the first frame after the screen opens
+    new_tables: Query<(), Added<LeaderboardTable>>,
+    tables: Query<Entity, With<LeaderboardTable>>,
+    mut titles: Query<&mut Text, With<LeaderboardTitle>>,
+) {
+    if !filter.is_changed() && new_tables.is_empty() {
+        return;
+    }
+
+    let mut text = titles.single_mut();
+    if let Some(section) = text.sections.first_mut() {
+        section.value = match filter.0 {
+            Some((width, height)) => format!("Top games on {width}x{height}"),
+            None => "Top games".to_string(),
+        };
+    }
+
+    let table = tables.single();
+    commands.entity(table).despawn_descendants();
+    commands.entity(table).with_children(|parent| {
+        let entries = records.leaderboard(filter.0);
+        if entries.is_empty() {
+            spawn_row(parent, &font_spec, ["No finished games yet".to_string()]);
+            return;
+        }
+        spawn_row(
+            parent,
+            &font_spec,
+            COLUMNS.map(|(name, _)| name.to_string()),
+        );
+        for (rank, entry) in entries.into_iter().enumerate() {
+            spawn_row(parent, &font_spec, entry_cells(rank + 1, entry));
+        }
+    });
+}
+
+fn entry_cells(rank: usize, entry: &LeaderboardEntry) -> [String; 8] {
+    [
+        rank.to_string(),
+        entry.score.to_string(),
+        entry.highest_tile.to_string(),
+        entry.moves.to_string(),
+        format_duration(entry.duration()),
+        format!("{}x{}", entry.width, entry.height),
+        entry.seed.to_string(),
+        format_date(entry.finished_at),
+    ]
+}
+
+// the cells line up with COLUMNS, a single cell is left to size itself
+fn spawn_row<const N: usize>(parent: &mut ChildBuilder, font_spec: &FontSpec, cells: [String; N]) {
+    parent.spawn(NodeBundle::default()).with_children(|parent| {
+        for (index, cell) in cells.into_iter().enumerate() {
+            let width = match N {
+                1 => Val::Auto,
+                _ => Val::Px(COLUMNS[index].1),
+            };
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size::new(width, Val::Auto),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        cell,
+                        TextStyle {
+                            font: font_spec.family.clone(),
+                            font_size: 18.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        }
+    });
+}
+
+fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
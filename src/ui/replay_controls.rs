@@ -0,0 +1,209 @@
+use bevy::{prelude::*, ui::RelativeCursorPosition};
+
+use super::spawn_button;
+use crate::{
+    colors,
+    data::{move_history::MoveHistory, replay_control::ReplayControl, replay_player::ReplayPlayer},
+    FontSpec, RunState,
+};
+
+pub struct ReplayControlsPlugin;
+
+impl Plugin for ReplayControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setup_replay_controls.in_schedule(OnEnter(RunState::Replaying)))
+            .add_system(despawn_replay_controls.in_schedule(OnExit(RunState::Replaying)))
+            .add_systems(
+                (replay_button_system, seek_bar_system, replay_status_system)
+                    .in_set(OnUpdate(RunState::Replaying)),
+            );
+    }
+}
+
+// root node of the control bar, so it can be removed in one go
+#[derive(Component)]
+struct ReplayControls;
+
+#[derive(Component)]
+struct ReplayButton(ReplayControl);
+
+#[derive(Component)]
+struct ReplayStatus;
+
+// clicking the bar seeks to that point of the replay
+#[derive(Component)]
+struct SeekBar;
+
+// the played part of the seek bar
+#[derive(Component)]
+struct SeekBarFill;
+
+fn setup_replay_controls(mut commands: Commands, font_spec: Res<FontSpec>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    align_items: AlignItems::FlexEnd,
+                    justify_content: JustifyContent::Center,
+                    padding: UiRect::all(Val::Px(50.0)),
+                    gap: Size::all(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            },
+            ReplayControls,
+        ))
+        .with_children(|parent| {
+            let buttons = [
+                ("|<", ReplayControl::Seek(0)),
+                ("<", ReplayControl::StepBack),
+                ("Play", ReplayControl::TogglePlay),
+                (">", ReplayControl::StepForward),
+                ("Slower", ReplayControl::Slower),
+                ("Faster", ReplayControl::Faster),
+            ];
+            for (label, control) in buttons {
+                let width = if label.len() <= 2 { 50.0 } else { 90.0 };
+                spawn_button(parent, &font_spec, width, label, ReplayButton(control));
+                // jumping to the end sits right after stepping forward
+                if control == ReplayControl::StepForward {
+                    spawn_button(
+                        parent,
+                        &font_spec,
+                        50.0,
+                        ">|",
+                        ReplayButton(ReplayControl::Seek(usize::MAX)),
+                    );
+                }
+            }
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(160.0), Val::Px(20.0)),
+                            margin: UiRect::bottom(Val::Px(15.0)),
+                            ..default()
+                        },
+                        background_color: BackgroundColor(colors::BOARD),
+                        ..default()
+                    },
+                    Interaction::default(),
+                    RelativeCursorPosition::default(),
+                    SeekBar,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Percent(0.0), Val::Percent(100.0)),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(colors::SCORE_BOX),
+                            ..default()
+                        },
+                        SeekBarFill,
+                    ));
+                });
+            parent.spawn((
+                TextBundle::from_section(
+                    "<status>",
+                    TextStyle {
+                        font: font_spec.family.clone(),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                ReplayStatus,
+            ));
+        });
+}
+
+fn despawn_replay_controls(mut commands: Commands, controls: Query<Entity, With<ReplayControls>>) {
+    for entity in controls.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn replay_button_system(
+    interaction_query: Query<(&Interaction, &ReplayButton), Changed<Interaction>>,
+    mut control_writer: EventWriter<ReplayControl>,
+) {
+    for (interaction, ReplayButton(control)) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            control_writer.send(*control);
+        }
+    }
+}
+
+type SeekBarClicks = (Changed<Interaction>, With<SeekBar>);
+
+fn seek_bar_system(
+    player: Res<ReplayPlayer>,
+    interaction_query: Query<(&Interaction, &RelativeCursorPosition), SeekBarClicks>,
+    mut control_writer: EventWriter<ReplayControl>,
+) {
+    for (interaction, cursor) in interaction_query.iter() {
+        let Some(normalized) = cursor.normalized else {
+            continue;
+        };
+        if *interaction == Interaction::Clicked {
+            let fraction = normalized.x.clamp(0.0, 1.0);
+            let target = (fraction * player.replay.moves.len() as f32).round() as usize;
+            control_writer.send(ReplayControl::Seek(target));
+        }
+    }
+}
+
+fn replay_status_system(
+    player: Res<ReplayPlayer>,
+    history: Res<MoveHistory>,
+    buttons: Query<(&ReplayButton, &Children)>,
+    mut status: Query<&mut Text, With<ReplayStatus>>,
+    mut texts: Query<&mut Text, Without<ReplayStatus>>,
+    mut fill: Query<&mut Style, With<SeekBarFill>>,
+) {
+    let played = history.moves().count();
+    let total = player.replay.moves.len();
+    let mut style = fill.single_mut();
+    let percent = 100.0 * played as f32 / total.max(1) as f32;
+    if style.size.width != Val::Percent(percent) {
+        style.size.width = Val::Percent(percent);
+    }
+
+    // only write on change, a changed Text is laid out again
+    let status_text = format!(
+        "move {played}/{total} at {} moves/s",
+        player.moves_per_second
+    );
+    let mut text = status.single_mut();
+    if text
+        .sections
+        .first()
+        .is_some_and(|section| section.value != status_text)
+    {
+        text.sections[0].value = status_text;
+    }
+
+    // the play button doubles as pause while the replay is running
+    for (ReplayButton(control), children) in buttons.iter() {
+        if *control != ReplayControl::TogglePlay {
+            continue;
+        }
+        let Some(mut text) = children
+            .first()
+            .and_then(|child| texts.get_mut(*child).ok())
+        else {
+            continue;
+        };
+        let label = if player.playing { "Pause" } else { "Play" };
+        if text
+            .sections
+            .first()
+            .is_some_and(|section| section.value != label)
+        {
+            text.sections[0].value = label.to_string();
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+
+use super::{spawn_button, spawn_overlay, styles, ScreenButton};
+use crate::{
+    colors,
+    data::{board_shift::BoardShift, records::Records, screen::Screen},
+    FontSpec,
+};
+
+pub struct StatsScreenPlugin;
+
+impl Plugin for StatsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setup_stats_screen.in_schedule(OnEnter(Screen::Stats)))
+            .add_system(despawn_stats_screen.in_schedule(OnExit(Screen::Stats)));
+    }
+}
+
+#[derive(Component)]
+struct StatsScreen;
+
+// the longest bar of the highest tile histogram
+const HISTOGRAM_WIDTH: f32 = 300.0;
+
+// The stats only change when a game ends, which can't happen while this screen is open,
+// so everything is filled in once when it opens.
+fn setup_stats_screen(mut commands: Commands, font_spec: Res<FontSpec>, records: Res<Records>) {
+    let stats = &records.stats;
+    let text_style = |font_size| TextStyle {
+        font: font_spec.family.clone(),
+        font_size,
+        color: Color::WHITE,
+    };
+
+    spawn_overlay(&mut commands, StatsScreen).with_children(|parent| {
+        parent.spawn(TextBundle::from_section("Statistics", text_style(40.0)));
+
+        parent
+            .spawn(NodeBundle {
+                style: styles::STATS_ROW,
+                ..default()
+            })
+            .with_children(|parent| {
+                let boxes = [
+                    ("Games", stats.games_played.to_string()),
+                    ("Win rate", format!("{:.0}%", stats.win_rate() * 100.0)),
+                    ("Average score", format!("{:.0}", stats.average_score())),
+                    ("Median score", format!("{:.0}", stats.median_score())),
+                    ("Average moves", format!("{:.0}", stats.average_moves())),
+                ];
+                for (label, value) in boxes {
+                    spawn_stat_box(parent, &font_spec, label, value);
+                }
+            });
+
+        parent.spawn(TextBundle::from_section("Moves played", text_style(20.0)));
+        parent
+            .spawn(NodeBundle {
+                style: styles::STATS_ROW,
+                ..default()
+            })
+            .with_children(|parent| {
+                let directions = [
+                    ("Up", BoardShift::Up),
+                    ("Down", BoardShift::Down),
+                    ("Left", BoardShift::Left),
+                    ("Right", BoardShift::Right),
+                ];
+                let total: u64 = directions
+                    .iter()
+                    .map(|(_, board_shift)| stats.shift_count(*board_shift))
+                    .sum();
+                for (label, board_shift) in directions {
+                    let count = stats.shift_count(board_shift);
+                    let share = match total {
+                        0 => 0.0,
+                        total => count as f32 / total as f32 * 100.0,
+                    };
+                    spawn_stat_box(parent, &font_spec, label, format!("{count} ({share:.0}%)"));
+                }
+            });
+
+        parent.spawn(TextBundle::from_section("Highest tile", text_style(20.0)));
+        if stats.highest_tiles.is_empty() {
+            parent.spawn(TextBundle::from_section(
+                "No finished games yet",
+                text_style(18.0),
+            ));
+        }
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    gap: Size::all(Val::Px(5.0)),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                let most_games = stats.highest_tiles.values().copied().max().unwrap_or(0);
+                for (tile, games) in stats.highest_tiles.iter() {
+                    parent
+                        .spawn(NodeBundle {
+                            style: styles::STATS_ROW,
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            parent
+                                .spawn(NodeBundle {
+                                    style: Style {
+                                        size: Size::width(Val::Px(60.0)),
+                                        justify_content: JustifyContent::FlexEnd,
+                                        ..default()
+                                    },
+                                    ..default()
+                                })
+                                .with_children(|parent| {
+                                    parent.spawn(TextBundle::from_section(
+                                        tile.to_string(),
+                                        text_style(18.0),
+                                    ));
+                                });
+                            // every bar gets at least a sliver so single games still show up
+                            let width = HISTOGRAM_WIDTH * *games as f32 / most_games as f32;
+                            parent.spawn(NodeBundle {
+                                style: Style {
+                                    size: Size::new(Val::Px(width.max(4.0)), Val::Px(18.0)),
+                                    ..default()
+                                },
+                                background_color: BackgroundColor(colors::SCORE_BOX),
+                                ..default()
+                            });
+                            parent.spawn(TextBundle::from_section(
+                                games.to_string(),
+                                text_style(18.0),
+                            ));
+                        });
+                }
+            });
+
+        spawn_button(
+            parent,
+            &font_spec,
+            90.0,
+            "Close",
+            ScreenButton(Screen::Board),
+        );
+    });
+}
+
+// same look as the score boxes at the top of the game screen
+fn spawn_stat_box(parent: &mut ChildBuilder, font_spec: &FontSpec, label: &str, value: String) {
+    parent
+        .spawn(NodeBundle {
+            style: styles::SCORE_CONTAINER,
+            background_color: BackgroundColor(colors::SCORE_BOX),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    label,
+                    TextStyle {
+                        font: font_spec.family.clone(),
+                        font_size: 15.0,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_text_alignment(TextAlignment::Center),
+            );
+            parent.spawn(
+                TextBundle::from_section(
+                    value,
+                    TextStyle {
+                        font: font_spec.family.clone(),
+                        font_size: 20.0,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_text_alignment(TextAlignment::Center),
+            );
+        });
+}
+
+fn despawn_stats_screen(mut commands: Commands, screens: Query<Entity, With<StatsScreen>>) {
+    for entity in screens.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -11,3 +11,14 @@ pub const SCORE_CONTAINER: Style = Style {
     },
     ..Style::DEFAULT
 };
+
+// a row of boxes or a histogram line on the stats screen
+pub const STATS_ROW: Style = Style {
+    align_items: AlignItems::Center,
+    justify_content: JustifyContent::Center,
+    gap: Size {
+        width: Val::Px(20.0),
+        height: Val::Px(20.0),
+    },
+    ..Style::DEFAULT
+};
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use crate::{colors, data::achievement_unlocked_event::AchievementUnlockedEvent, FontSpec};
+
+pub struct ToastsPlugin;
+
+impl Plugin for ToastsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_toast_area)
+            .add_systems((show_toasts, expire_toasts));
+    }
+}
+
+// how long a toast stays up, in seconds
+const TOAST_DURATION: f32 = 4.0;
+
+// bottom right corner that toasts stack up in
+#[derive(Component)]
+struct ToastArea;
+
+#[derive(Component)]
+struct Toast(Timer);
+
+fn setup_toast_area(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    right: Val::Px(50.0),
+                    bottom: Val::Px(50.0),
+                    ..default()
+                },
+                flex_direction: FlexDirection::ColumnReverse,
+                gap: Size::all(Val::Px(10.0)),
+                ..default()
+            },
+            // toasts stay on top of the screens shown over the board
+            z_index: ZIndex::Global(1),
+            ..default()
+        },
+        ToastArea,
+    ));
+}
+
+fn show_toasts(
+    mut commands: Commands,
+    mut unlocked_reader: EventReader<AchievementUnlockedEvent>,
+    font_spec: Res<FontSpec>,
+    areas: Query<Entity, With<ToastArea>>,
+) {
+    let area = areas.single();
+    for AchievementUnlockedEvent { achievement } in unlocked_reader.iter() {
+        commands.entity(area).with_children(|parent| {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            padding: UiRect::all(Val::Px(10.0)),
+                            ..default()
+                        },
+                        background_color: BackgroundColor(colors::SCORE_BOX),
+                        ..default()
+                    },
+                    Toast(Timer::from_seconds(TOAST_DURATION, TimerMode::Once)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        format!("Achievement unlocked: {}", achievement.name),
+                        TextStyle {
+                            font: font_spec.family.clone(),
+                            font_size: 20.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                    parent.spawn(TextBundle::from_section(
+                        achievement.description,
+                        TextStyle {
+                            font: font_spec.family.clone(),
+                            font_size: 15.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        });
+    }
+}
+
+fn expire_toasts(mut commands: Commands, time: Res<Time>, mut toasts: Query<(Entity, &mut Toast)>) {
+    for (entity, mut toast) in toasts.iter_mut() {
+        if toast.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
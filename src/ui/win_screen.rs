@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+
+use super::{spawn_button, spawn_overlay};
+use crate::{FontSpec, Game, RunState};
+
+pub struct WinScreenPlugin;
+
+impl Plugin for WinScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setup_win_screen.in_schedule(OnEnter(RunState::Won)))
+            .add_system(despawn_win_screen.in_schedule(OnExit(RunState::Won)))
+            .add_system(win_button_system.in_set(OnUpdate(RunState::Won)));
+    }
+}
+
+#[derive(Component)]
+struct WinScreen;
+
+#[derive(Component)]
+enum WinButton {
+    KeepGoing,
+    NewGame,
+}
+
+fn setup_win_screen(mut commands: Commands, font_spec: Res<FontSpec>) {
+    spawn_overlay(&mut commands, WinScreen).with_children(|parent| {
+        parent.spawn(TextBundle::from_section(
+            "You win!",
+            TextStyle {
+                font: font_spec.family.clone(),
+                font_size: 40.0,
+                color: Color::WHITE,
+            },
+        ));
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    gap: Size::all(Val::Px(20.0)),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                spawn_button(
+                    parent,
+                    &font_spec,
+                    150.0,
+                    "Keep going",
+                    WinButton::KeepGoing,
+                );
+                spawn_button(parent, &font_spec, 150.0, "New Game", WinButton::NewGame);
+            });
+    });
+}
+
+fn despawn_win_screen(mut commands: Commands, screens: Query<Entity, With<WinScreen>>) {
+    for entity in screens.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn win_button_system(
+    interaction_query: Query<(&Interaction, &WinButton), Changed<Interaction>>,
+    mut game: ResMut<Game>,
+    mut next_state: ResMut<NextState<RunState>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction == Interaction::Clicked {
+            // keep going continues the current board, Game::won stays set so the
+            // win screen doesn't show up again
+            game.resuming = matches!(button, WinButton::KeepGoing);
+            next_state.set(RunState::Playing);
+        }
+    }
+}